@@ -0,0 +1,20 @@
+-- Verifiable credentials issued for verification tiers
+CREATE SEQUENCE credential_status_seq MINVALUE 0 START 0;
+
+CREATE TABLE verifiable_credentials (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    verification_tier VARCHAR(50) NOT NULL,
+    status_list_id INTEGER NOT NULL,
+    status_list_index INTEGER NOT NULL,
+    jwt TEXT NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    issued_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    -- Each credential owns exactly one bit of a status list
+    UNIQUE(status_list_id, status_list_index)
+);
+
+CREATE INDEX idx_verifiable_credentials_user_id ON verifiable_credentials(user_id);
+CREATE INDEX idx_verifiable_credentials_revoked ON verifiable_credentials(status_list_id) WHERE revoked_at IS NOT NULL;
@@ -0,0 +1,26 @@
+-- Vouches: a stronger, directed trust signal between accepted connections
+CREATE TABLE vouches (
+    voucher_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    vouchee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (voucher_id, vouchee_id),
+    CHECK (voucher_id != vouchee_id)
+);
+
+CREATE INDEX idx_vouches_vouchee_id ON vouches(vouchee_id);
+
+-- Trust scores, recomputed by the background job
+CREATE TABLE trust_scores (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    score DOUBLE PRECISION NOT NULL DEFAULT 0,
+    tier_component DOUBLE PRECISION NOT NULL DEFAULT 0,
+    age_component DOUBLE PRECISION NOT NULL DEFAULT 0,
+    vouch_component DOUBLE PRECISION NOT NULL DEFAULT 0,
+    propagation_component DOUBLE PRECISION NOT NULL DEFAULT 0,
+    -- Raw EigenTrust value, kept to warm-start the next run
+    propagated_trust DOUBLE PRECISION NOT NULL DEFAULT 0,
+    computed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_trust_scores_score ON trust_scores(score DESC);
@@ -0,0 +1,22 @@
+-- Profiles, one per user
+CREATE TABLE user_profiles (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    headline VARCHAR(120),
+    summary TEXT,
+    location VARCHAR(100),
+    website VARCHAR(255),
+    visibility VARCHAR(20) NOT NULL DEFAULT 'public', -- 'public', 'connections', 'private'
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CHECK (visibility IN ('public', 'connections', 'private'))
+);
+
+CREATE TRIGGER update_user_profiles_updated_at
+    BEFORE UPDATE ON user_profiles
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Existing users start with an empty public profile
+INSERT INTO user_profiles (user_id)
+SELECT id FROM users;
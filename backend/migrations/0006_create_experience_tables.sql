@@ -0,0 +1,65 @@
+-- Canonical companies and schools that positions and education entries link to
+CREATE TABLE organizations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(20) NOT NULL, -- 'company', 'school'
+    name VARCHAR(200) NOT NULL,
+    normalized_name VARCHAR(200) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE(kind, normalized_name),
+    CHECK (kind IN ('company', 'school'))
+);
+
+CREATE TABLE positions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL,
+    company_name VARCHAR(200) NOT NULL,
+    title VARCHAR(200) NOT NULL,
+    start_year INTEGER NOT NULL,
+    start_month INTEGER NOT NULL,
+    end_year INTEGER,
+    end_month INTEGER,
+    is_current BOOLEAN NOT NULL DEFAULT FALSE,
+    description TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CHECK (start_month BETWEEN 1 AND 12),
+    CHECK (end_month IS NULL OR end_month BETWEEN 1 AND 12),
+    CHECK ((end_year IS NULL) = (end_month IS NULL)),
+    CHECK (NOT (is_current AND end_year IS NOT NULL))
+);
+
+CREATE INDEX idx_positions_user_id ON positions(user_id, sort_order);
+CREATE INDEX idx_positions_organization_id ON positions(organization_id);
+
+CREATE TABLE education (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL,
+    school_name VARCHAR(200) NOT NULL,
+    degree VARCHAR(200),
+    field_of_study VARCHAR(200),
+    start_year INTEGER,
+    end_year INTEGER,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CHECK (start_year IS NULL OR end_year IS NULL OR end_year >= start_year)
+);
+
+CREATE INDEX idx_education_user_id ON education(user_id, sort_order);
+CREATE INDEX idx_education_organization_id ON education(organization_id);
+
+CREATE TRIGGER update_positions_updated_at
+    BEFORE UPDATE ON positions
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TRIGGER update_education_updated_at
+    BEFORE UPDATE ON education
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,90 @@
+-- Normalized skills; every name a skill is known by lives in skill_aliases
+CREATE TABLE skills (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    normalized_name VARCHAR(100) UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE skill_aliases (
+    alias VARCHAR(100) PRIMARY KEY, -- normalized
+    skill_id UUID NOT NULL REFERENCES skills(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_skill_aliases_skill_id ON skill_aliases(skill_id);
+
+CREATE TABLE user_skills (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    skill_id UUID NOT NULL REFERENCES skills(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (user_id, skill_id)
+);
+
+CREATE INDEX idx_user_skills_skill_id ON user_skills(skill_id);
+
+CREATE TABLE endorsements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    endorser_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    skill_id UUID NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    -- One endorsement per endorser per skill
+    UNIQUE(endorser_id, user_id, skill_id),
+    FOREIGN KEY (user_id, skill_id) REFERENCES user_skills(user_id, skill_id) ON DELETE CASCADE,
+    CHECK (endorser_id != user_id)
+);
+
+CREATE INDEX idx_endorsements_user_skill ON endorsements(user_id, skill_id);
+
+-- Folds `source` into `target`: aliases, user skills and endorsements move over,
+-- duplicates collapse, and `source` is deleted.
+CREATE OR REPLACE FUNCTION merge_skills(source UUID, target UUID)
+RETURNS VOID AS $$
+BEGIN
+    IF source = target THEN
+        RETURN;
+    END IF;
+
+    UPDATE skill_aliases SET skill_id = target WHERE skill_id = source;
+
+    INSERT INTO user_skills (user_id, skill_id, created_at)
+    SELECT user_id, target, created_at FROM user_skills WHERE skill_id = source
+    ON CONFLICT (user_id, skill_id) DO NOTHING;
+
+    INSERT INTO endorsements (endorser_id, user_id, skill_id, created_at)
+    SELECT endorser_id, user_id, target, created_at FROM endorsements WHERE skill_id = source
+    ON CONFLICT (endorser_id, user_id, skill_id) DO NOTHING;
+
+    DELETE FROM skills WHERE id = source;
+END;
+$$ language 'plpgsql';
+
+-- Seed common skills and the names people use for them
+WITH seed(name, aliases) AS (
+    VALUES
+        ('JavaScript', ARRAY['javascript', 'js', 'ecmascript', 'es6']),
+        ('TypeScript', ARRAY['typescript', 'ts']),
+        ('Python', ARRAY['python', 'py', 'python3']),
+        ('Rust', ARRAY['rust', 'rustlang']),
+        ('Go', ARRAY['go', 'golang']),
+        ('C++', ARRAY['c++', 'cpp']),
+        ('C#', ARRAY['c#', 'csharp']),
+        ('Java', ARRAY['java']),
+        ('React', ARRAY['react', 'reactjs', 'react.js']),
+        ('Node.js', ARRAY['node.js', 'nodejs', 'node']),
+        ('PostgreSQL', ARRAY['postgresql', 'postgres', 'psql']),
+        ('Kubernetes', ARRAY['kubernetes', 'k8s']),
+        ('Machine Learning', ARRAY['machine learning', 'ml']),
+        ('Amazon Web Services', ARRAY['amazon web services', 'aws']),
+        ('Product Management', ARRAY['product management', 'pm'])
+),
+inserted AS (
+    INSERT INTO skills (name, normalized_name)
+    SELECT name, aliases[1] FROM seed
+    RETURNING id, name
+)
+INSERT INTO skill_aliases (alias, skill_id)
+SELECT UNNEST(seed.aliases), inserted.id
+FROM seed JOIN inserted ON inserted.name = seed.name;
@@ -0,0 +1,40 @@
+-- Written recommendations between accepted connections
+CREATE TABLE recommendations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    author_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    recipient_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- 'requested', 'pending_approval', 'visible', 'hidden'
+    status VARCHAR(20) NOT NULL DEFAULT 'requested',
+    request_message VARCHAR(500),
+    relationship VARCHAR(40),
+    body TEXT,
+    version INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    -- One recommendation per author for each recipient
+    UNIQUE(author_id, recipient_id),
+    CHECK (author_id != recipient_id),
+    CHECK (status IN ('requested', 'pending_approval', 'visible', 'hidden')),
+    CHECK (status = 'requested' OR body IS NOT NULL)
+);
+
+CREATE INDEX idx_recommendations_recipient_id ON recommendations(recipient_id, status);
+CREATE INDEX idx_recommendations_author_id ON recommendations(author_id, status);
+
+-- Every version the author has written, oldest first
+CREATE TABLE recommendation_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    recommendation_id UUID NOT NULL REFERENCES recommendations(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    relationship VARCHAR(40) NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE(recommendation_id, version)
+);
+
+CREATE TRIGGER update_recommendations_updated_at
+    BEFORE UPDATE ON recommendations
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,8 @@
+-- The current uploaded profile picture per user; thumbnails live in storage
+-- under avatars/<user_id>/<upload_id>/<size>.jpg
+CREATE TABLE profile_pictures (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    upload_id UUID NOT NULL,
+    sizes INTEGER[] NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
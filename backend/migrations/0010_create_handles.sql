@@ -0,0 +1,31 @@
+ALTER TABLE users
+    ADD COLUMN handle VARCHAR(30),
+    ADD COLUMN handle_changed_at TIMESTAMP WITH TIME ZONE;
+
+-- Existing users get a slug of their first name and last initial plus a
+-- short hash, which can't collide. The rest of the last name stays out, as
+-- it may be hidden.
+UPDATE users
+SET handle = COALESCE(
+        NULLIF(trim(both '-' from left(trim(both '-' from lower(regexp_replace(
+            split_part(btrim(full_name), ' ', 1) || COALESCE(' ' || substring(btrim(full_name) from '\s(\S)\S*$'), ''),
+            '[^a-zA-Z0-9]+', '-', 'g'
+        ))), 20)), ''),
+        'member'
+    ) || '-' || substr(md5(id::text), 1, 6);
+
+ALTER TABLE users ALTER COLUMN handle SET NOT NULL;
+
+CREATE UNIQUE INDEX idx_users_handle_lower ON users (lower(handle));
+
+-- Handles a user moved away from. They keep redirecting to the owner, and
+-- stay reserved from everyone else, until expires_at.
+CREATE TABLE handle_history (
+    handle VARCHAR(30) NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    retired_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_handle_history_handle_lower ON handle_history (lower(handle));
+CREATE INDEX idx_handle_history_user_id ON handle_history (user_id);
@@ -0,0 +1,35 @@
+-- Who may see each part of a user's data:
+-- 'public', 'connections', 'second_degree' (connections and their connections) or 'only_me'
+CREATE TABLE privacy_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(20) NOT NULL DEFAULT 'connections',
+    -- Headline, summary, location, website, experience, skills and recommendations
+    profile_fields VARCHAR(20) NOT NULL DEFAULT 'public',
+    connection_list VARCHAR(20) NOT NULL DEFAULT 'connections',
+    -- Hidden last names are shortened to an initial
+    last_name VARCHAR(20) NOT NULL DEFAULT 'public',
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CHECK (email IN ('public', 'connections', 'second_degree', 'only_me')),
+    CHECK (profile_fields IN ('public', 'connections', 'second_degree', 'only_me')),
+    CHECK (connection_list IN ('public', 'connections', 'second_degree', 'only_me')),
+    CHECK (last_name IN ('public', 'connections', 'second_degree', 'only_me'))
+);
+
+CREATE TRIGGER update_privacy_settings_updated_at
+    BEFORE UPDATE ON privacy_settings
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- The whole-profile visibility switch becomes the profile fields setting
+INSERT INTO privacy_settings (user_id, profile_fields)
+SELECT u.id,
+       CASE p.visibility
+           WHEN 'connections' THEN 'connections'
+           WHEN 'private' THEN 'only_me'
+           ELSE 'public'
+       END
+FROM users u
+LEFT JOIN user_profiles p ON p.user_id = u.id;
+
+ALTER TABLE user_profiles DROP COLUMN visibility;
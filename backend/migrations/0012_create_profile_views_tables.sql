@@ -0,0 +1,30 @@
+-- Viewers browsing privately show up only as "someone at <their company>"
+ALTER TABLE privacy_settings ADD COLUMN browse_privately BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- One row per viewer, per viewed profile, per day
+CREATE TABLE profile_views (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    viewer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    viewed_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    view_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    viewed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    -- Snapshot of the viewer's private browsing mode and employer at view time
+    anonymous BOOLEAN NOT NULL DEFAULT FALSE,
+    viewer_organization VARCHAR(200),
+
+    UNIQUE(viewer_id, viewed_id, view_date),
+    CHECK (viewer_id != viewed_id)
+);
+
+CREATE INDEX idx_profile_views_viewed_id ON profile_views(viewed_id, viewed_at DESC);
+CREATE INDEX idx_profile_views_view_date ON profile_views(view_date);
+
+-- Daily counts for raw views past their retention period
+CREATE TABLE profile_view_daily (
+    viewed_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    view_date DATE NOT NULL,
+    view_count INTEGER NOT NULL,
+    anonymous_count INTEGER NOT NULL,
+
+    PRIMARY KEY (viewed_id, view_date)
+);
@@ -0,0 +1,23 @@
+-- 'member' or 'moderator'; moderators can review any user's profile history
+ALTER TABLE users ADD COLUMN role VARCHAR(20) NOT NULL DEFAULT 'member'
+    CHECK (role IN ('member', 'moderator'));
+
+-- A full snapshot of the profile fields after every change, with what changed
+CREATE TABLE profile_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    -- Who made the change; kept when that account is deleted
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    headline VARCHAR(120),
+    summary TEXT,
+    location VARCHAR(100),
+    website VARCHAR(255),
+    -- [{"field": ..., "old": ..., "new": ...}]
+    changes JSONB NOT NULL,
+    -- Set when this revision restored an earlier one
+    reverted_to INTEGER,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE(user_id, version)
+);
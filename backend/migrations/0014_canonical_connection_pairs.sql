@@ -0,0 +1,40 @@
+-- When a connection was accepted. Nothing has touched an accepted row since
+-- it was accepted, so its updated_at still says when that was.
+ALTER TABLE connections ADD COLUMN accepted_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE connections SET accepted_at = COALESCE(updated_at, created_at, NOW()) WHERE status = 'accepted';
+
+-- One row per unordered pair of users. Where both directions exist, keep the
+-- furthest along (accepted, then pending, then rejected; oldest on ties).
+-- Two pending requests that crossed become an accepted connection.
+WITH ranked AS (
+    SELECT id,
+           ROW_NUMBER() OVER (
+               PARTITION BY LEAST(sender_id, receiver_id), GREATEST(sender_id, receiver_id)
+               ORDER BY CASE status WHEN 'accepted' THEN 0 WHEN 'pending' THEN 1 ELSE 2 END, created_at, id
+           ) AS rank,
+           COUNT(*) FILTER (WHERE status = 'pending') OVER (
+               PARTITION BY LEAST(sender_id, receiver_id), GREATEST(sender_id, receiver_id)
+           ) AS pending_count
+    FROM connections
+),
+crossed AS (
+    UPDATE connections c SET status = 'accepted', accepted_at = NOW()
+    FROM ranked r
+    WHERE c.id = r.id AND r.rank = 1 AND c.status = 'pending' AND r.pending_count > 1
+)
+DELETE FROM connections c
+USING ranked r
+WHERE c.id = r.id AND r.rank > 1;
+
+ALTER TABLE connections DROP CONSTRAINT connections_sender_id_receiver_id_key;
+DROP INDEX idx_connections_sender_receiver;
+
+CREATE UNIQUE INDEX idx_connections_pair
+    ON connections (LEAST(sender_id, receiver_id), GREATEST(sender_id, receiver_id));
+
+ALTER TABLE connections ADD CONSTRAINT connections_status_check
+    CHECK (status IN ('pending', 'accepted', 'rejected'));
+
+ALTER TABLE connections ADD CONSTRAINT connections_accepted_at_check
+    CHECK (status <> 'accepted' OR accepted_at IS NOT NULL);
@@ -0,0 +1,16 @@
+-- Every rejected request, kept after the connection row changes or goes away,
+-- for re-request cooldowns and per-sender rejection rates
+CREATE TABLE connection_rejections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    sender_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    receiver_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    rejected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_connection_rejections_sender ON connection_rejections(sender_id, rejected_at DESC);
+CREATE INDEX idx_connection_rejections_pair ON connection_rejections(sender_id, receiver_id, rejected_at DESC);
+
+INSERT INTO connection_rejections (sender_id, receiver_id, rejected_at)
+SELECT sender_id, receiver_id, COALESCE(updated_at, NOW())
+FROM connections
+WHERE status = 'rejected';
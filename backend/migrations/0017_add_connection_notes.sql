@@ -0,0 +1,3 @@
+-- The sender's personal note, and the receiver's reply when accepting
+ALTER TABLE connections ADD COLUMN note VARCHAR(300);
+ALTER TABLE connections ADD COLUMN reply VARCHAR(300);
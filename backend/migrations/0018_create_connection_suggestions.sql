@@ -0,0 +1,23 @@
+-- "People you may know", precomputed by a background job
+CREATE TABLE connection_suggestions (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    suggested_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    score DOUBLE PRECISION NOT NULL,
+    mutual_connections INTEGER NOT NULL,
+    shared_organizations INTEGER NOT NULL,
+    same_location BOOLEAN NOT NULL,
+    computed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (user_id, suggested_id)
+);
+
+CREATE INDEX idx_connection_suggestions_ranked ON connection_suggestions(user_id, score DESC);
+
+-- Suggestions a user dismissed are never suggested again
+CREATE TABLE dismissed_suggestions (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    dismissed_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (user_id, dismissed_id)
+);
@@ -0,0 +1,28 @@
+-- One-way follows, separate from mutual connections
+CREATE TABLE follows (
+    follower_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    followed_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (follower_id, followed_id),
+    CHECK (follower_id != followed_id)
+);
+
+CREATE INDEX idx_follows_followed_id ON follows(followed_id);
+
+-- Creators lead with Follow instead of Connect
+ALTER TABLE users ADD COLUMN creator_mode BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Everyone following everyone: explicit follows plus both sides of every
+-- accepted connection, dated from when it was accepted. Where both apply,
+-- the connection wins.
+CREATE VIEW follow_edges AS
+SELECT DISTINCT ON (follower_id, followed_id) follower_id, followed_id, since, source
+FROM (
+    SELECT follower_id, followed_id, created_at AS since, 'follow'::varchar AS source FROM follows
+    UNION ALL
+    SELECT sender_id, receiver_id, accepted_at, 'connection'::varchar FROM connections WHERE status = 'accepted'
+    UNION ALL
+    SELECT receiver_id, sender_id, accepted_at, 'connection'::varchar FROM connections WHERE status = 'accepted'
+) edges
+ORDER BY follower_id, followed_id, source;
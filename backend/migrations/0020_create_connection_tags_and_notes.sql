@@ -0,0 +1,20 @@
+-- Private CRM data a user keeps about their connections; only the owner sees it
+CREATE TABLE connection_tags (
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    contact_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tag VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (owner_id, contact_id, tag)
+);
+
+CREATE INDEX idx_connection_tags_owner_tag ON connection_tags(owner_id, tag);
+
+CREATE TABLE connection_private_notes (
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    contact_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (owner_id, contact_id)
+);
@@ -0,0 +1,15 @@
+-- Receivers may ignore a request instead of rejecting it, and requests left
+-- pending expire. Expiry counts from when a request was last sent, which
+-- a re-sent request's created_at no longer says.
+ALTER TABLE connections ADD COLUMN requested_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW();
+
+UPDATE connections SET requested_at = COALESCE(updated_at, created_at, NOW()) WHERE status <> 'accepted';
+UPDATE connections SET requested_at = COALESCE(created_at, NOW()) WHERE status = 'accepted';
+
+ALTER TABLE connections DROP CONSTRAINT connections_status_check;
+ALTER TABLE connections ADD CONSTRAINT connections_status_check
+    CHECK (status IN ('pending', 'accepted', 'rejected', 'ignored', 'expired'));
+
+CREATE INDEX idx_connections_sender_requested ON connections(sender_id, requested_at DESC);
+CREATE INDEX idx_connections_pending_requested ON connections(requested_at)
+    WHERE status = 'pending';
@@ -2,7 +2,7 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
-use jsonwebtoken::{encode, EncodingKey, Header};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
@@ -14,6 +14,18 @@ pub struct Claims {
     pub exp: usize,     // expiration time
 }
 
+/// Claims of a profile share link. Deliberately has no `email`, so a share
+/// token can never pass as a session token.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShareClaims {
+    pub sub: Uuid,      // profile owner
+    pub purpose: String,
+    pub exp: usize,
+}
+
+const SHARE_PURPOSE: &str = "connect";
+const SHARE_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
 #[derive(Clone)]  // ← ADD THIS LINE
 pub struct AuthService {
     jwt_secret: String,
@@ -59,4 +71,37 @@ impl AuthService {
             &EncodingKey::from_secret(self.jwt_secret.as_ref()),
         )
     }
+
+    /// Signs a link that lets whoever opens it request a connection with `user_id`.
+    pub fn generate_share_token(&self, user_id: Uuid) -> Result<String, jsonwebtoken::errors::Error> {
+        let expiration = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() + SHARE_TTL_SECS;
+
+        let claims = ShareClaims {
+            sub: user_id,
+            purpose: SHARE_PURPOSE.to_string(),
+            exp: expiration as usize,
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.jwt_secret.as_ref()),
+        )
+    }
+
+    /// The profile owner a share token points at, if it is genuine and unexpired.
+    pub fn verify_share_token(&self, token: &str) -> Option<Uuid> {
+        let claims = decode::<ShareClaims>(
+            token,
+            &DecodingKey::from_secret(self.jwt_secret.as_ref()),
+            &Validation::default(),
+        )
+        .ok()?
+        .claims;
+
+        (claims.purpose == SHARE_PURPOSE).then_some(claims.sub)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,113 @@
+//! Blocking and muting. A block works in both directions: neither user can
+//! see or reach the other. A mute only hides the muted user's activity from
+//! the muter, and the muted user can't tell.
+
+use sqlx::{PgConnection, PgPool};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::follows;
+use crate::relationships;
+
+/// Users in `others` who blocked `user_id` or whom `user_id` blocked.
+pub async fn blocked_among(pool: &PgPool, user_id: Uuid, others: &[Uuid]) -> Result<HashSet<Uuid>, sqlx::Error> {
+    let ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT blocked_id FROM user_blocks WHERE blocker_id = $1 AND blocked_id = ANY($2)
+         UNION
+         SELECT blocker_id FROM user_blocks WHERE blocked_id = $1 AND blocker_id = ANY($2)"
+    )
+        .bind(user_id)
+        .bind(others)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(ids.into_iter().collect())
+}
+
+/// For each of `user_ids`, everyone who blocked them or whom they blocked,
+/// in one query. Users with no blocks either way are left out.
+pub async fn blocked_by_any(pool: &PgPool, user_ids: &[Uuid]) -> Result<HashMap<Uuid, HashSet<Uuid>>, sqlx::Error> {
+    let pairs: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        "SELECT blocker_id, blocked_id FROM user_blocks WHERE blocker_id = ANY($1)
+         UNION
+         SELECT blocked_id, blocker_id FROM user_blocks WHERE blocked_id = ANY($1)"
+    )
+        .bind(user_ids)
+        .fetch_all(pool)
+        .await?;
+
+    let mut blocked: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+    for (user_id, other) in pairs {
+        blocked.entry(user_id).or_default().insert(other);
+    }
+
+    Ok(blocked)
+}
+
+/// Whether either user has blocked the other.
+pub async fn is_blocked(conn: &mut PgConnection, a: Uuid, b: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS (
+             SELECT 1 FROM user_blocks
+             WHERE (blocker_id = $1 AND blocked_id = $2) OR (blocker_id = $2 AND blocked_id = $1)
+         )"
+    )
+        .bind(a)
+        .bind(b)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+/// Blocks `blocked_id` for `blocker_id` and ends whatever was between them:
+/// the connection and what hangs off it, follows, and any open request
+/// either way.
+/// Returns false if the block already existed.
+pub async fn block(conn: &mut PgConnection, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, sqlx::Error> {
+    relationships::lock_pair(conn, blocker_id, blocked_id).await?;
+
+    let created = sqlx::query(
+        "INSERT INTO user_blocks (blocker_id, blocked_id) VALUES ($1, $2)
+         ON CONFLICT (blocker_id, blocked_id) DO NOTHING"
+    )
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected() > 0;
+
+    relationships::disconnect(conn, blocker_id, blocked_id).await?;
+    follows::remove_between(conn, blocker_id, blocked_id).await?;
+
+    // Pending and rejected requests aren't covered by disconnecting
+    sqlx::query(
+        "DELETE FROM connections
+         WHERE (sender_id = $1 AND receiver_id = $2) OR (sender_id = $2 AND receiver_id = $1)"
+    )
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        "DELETE FROM recommendations
+         WHERE status IN ('requested', 'pending_approval')
+           AND ((author_id = $1 AND recipient_id = $2) OR (author_id = $2 AND recipient_id = $1))"
+    )
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(created)
+}
+
+/// Lifts a block. Nothing that the block removed comes back.
+pub async fn unblock(pool: &PgPool, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM user_blocks WHERE blocker_id = $1 AND blocked_id = $2")
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,215 @@
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Share of the search ranking that comes from completeness; the rest is trust.
+pub const SEARCH_WEIGHT: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    Photo,
+    Headline,
+    Summary,
+    Location,
+    Experience,
+    Education,
+    Skills,
+    VerifiedEmail,
+    Connections,
+}
+
+/// Every step with its weight and how many of the thing it takes. Weights add up to 100.
+const CHECKLIST: &[(Step, u32, i64)] = &[
+    (Step::Experience, 20, 1),
+    (Step::Photo, 15, 1),
+    (Step::Headline, 15, 1),
+    (Step::Connections, 15, 5),
+    (Step::VerifiedEmail, 10, 1),
+    (Step::Education, 10, 1),
+    (Step::Summary, 5, 1),
+    (Step::Location, 5, 1),
+    (Step::Skills, 5, 3),
+];
+
+impl Step {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Step::Photo => "Add a profile photo",
+            Step::Headline => "Write a headline",
+            Step::Summary => "Write a summary",
+            Step::Location => "Add your location",
+            Step::Experience => "Add your current or past position",
+            Step::Education => "Add your education",
+            Step::Skills => "List at least 3 skills",
+            Step::VerifiedEmail => "Verify your email address",
+            Step::Connections => "Make your first 5 connections",
+        }
+    }
+
+    /// The `Facts` column this step is counted in.
+    fn column(&self) -> &'static str {
+        match self {
+            Step::Photo => "photo",
+            Step::Headline => "headline",
+            Step::Summary => "summary",
+            Step::Location => "location",
+            Step::Experience => "experience",
+            Step::Education => "education",
+            Step::Skills => "skills",
+            Step::VerifiedEmail => "verified_email",
+            Step::Connections => "connections",
+        }
+    }
+
+    /// SQL counting this step's thing for the user `u`, whose profile is joined as `p`.
+    fn count_sql(&self) -> &'static str {
+        match self {
+            Step::Photo => "CASE WHEN u.profile_picture_url IS NOT NULL THEN 1 ELSE 0 END",
+            Step::Headline => "CASE WHEN p.headline IS NOT NULL THEN 1 ELSE 0 END",
+            Step::Summary => "CASE WHEN p.summary IS NOT NULL THEN 1 ELSE 0 END",
+            Step::Location => "CASE WHEN p.location IS NOT NULL THEN 1 ELSE 0 END",
+            Step::VerifiedEmail => {
+                "CASE WHEN COALESCE(u.email_verified, FALSE)
+                          OR u.verification_tier IN ('email_verified', 'identity_verified') THEN 1 ELSE 0 END"
+            }
+            Step::Experience => "(SELECT COUNT(*) FROM positions WHERE user_id = u.id)",
+            Step::Education => "(SELECT COUNT(*) FROM education WHERE user_id = u.id)",
+            Step::Skills => "(SELECT COUNT(*) FROM user_skills WHERE user_id = u.id)",
+            Step::Connections => {
+                "(SELECT COUNT(*) FROM connections
+                  WHERE status = 'accepted' AND (sender_id = u.id OR receiver_id = u.id))"
+            }
+        }
+    }
+}
+
+/// How many of each step's thing a user has, one column per step.
+#[derive(Debug, FromRow)]
+struct Facts {
+    user_id: Uuid,
+    photo: i64,
+    headline: i64,
+    summary: i64,
+    location: i64,
+    verified_email: i64,
+    experience: i64,
+    education: i64,
+    skills: i64,
+    connections: i64,
+}
+
+impl Facts {
+    fn count(&self, step: Step) -> i64 {
+        match step {
+            Step::Photo => self.photo,
+            Step::Headline => self.headline,
+            Step::Summary => self.summary,
+            Step::Location => self.location,
+            Step::VerifiedEmail => self.verified_email,
+            Step::Experience => self.experience,
+            Step::Education => self.education,
+            Step::Skills => self.skills,
+            Step::Connections => self.connections,
+        }
+    }
+
+    fn score(&self) -> u32 {
+        CHECKLIST
+            .iter()
+            .filter(|(step, _, required)| self.count(*step) >= *required)
+            .map(|(_, weight, _)| weight)
+            .sum()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChecklistItem {
+    pub step: Step,
+    pub label: &'static str,
+    pub weight: u32,
+    pub done: bool,
+    pub progress: i64,
+    pub required: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Completeness {
+    pub score: u32,
+    pub completed: Vec<ChecklistItem>,
+    /// Highest weight first, so the most valuable next step leads.
+    pub missing: Vec<ChecklistItem>,
+}
+
+async fn load_facts(pool: &PgPool, user_ids: &[Uuid]) -> Result<Vec<Facts>, sqlx::Error> {
+    let counts: Vec<String> = CHECKLIST
+        .iter()
+        .map(|(step, _, _)| format!("({})::bigint AS {}", step.count_sql(), step.column()))
+        .collect();
+
+    sqlx::query_as(&format!(
+        "SELECT u.id AS user_id, {}
+         FROM users u
+         LEFT JOIN user_profiles p ON p.user_id = u.id
+         WHERE u.id = ANY($1)",
+        counts.join(", ")
+    ))
+        .bind(user_ids)
+        .fetch_all(pool)
+        .await
+}
+
+/// The full checklist for one user, or `None` if the user doesn't exist.
+pub async fn checklist(pool: &PgPool, user_id: Uuid) -> Result<Option<Completeness>, sqlx::Error> {
+    let Some(facts) = load_facts(pool, &[user_id]).await?.pop() else {
+        return Ok(None);
+    };
+
+    let (completed, missing): (Vec<_>, Vec<_>) = CHECKLIST
+        .iter()
+        .map(|&(step, weight, required)| {
+            let progress = facts.count(step).min(required);
+            ChecklistItem {
+                step,
+                label: step.label(),
+                weight,
+                done: progress >= required,
+                progress,
+                required,
+            }
+        })
+        .partition(|item| item.done);
+
+    Ok(Some(Completeness { score: facts.score(), completed, missing }))
+}
+
+/// Scores from 0 to 100 for many users at once, for ranking.
+pub async fn scores(pool: &PgPool, user_ids: &[Uuid]) -> Result<HashMap<Uuid, u32>, sqlx::Error> {
+    Ok(load_facts(pool, user_ids)
+        .await?
+        .into_iter()
+        .map(|facts| (facts.user_id, facts.score()))
+        .collect())
+}
+
+/// Blends a trust score (0-100) with a completeness score (0-100) into one ranking value.
+pub fn ranking_signal(trust_score: Option<f64>, completeness: u32) -> f64 {
+    trust_score.unwrap_or(0.0) * (1.0 - SEARCH_WEIGHT) + completeness as f64 * SEARCH_WEIGHT
+}
+
+/// `ranking_signal` in SQL, for ranking inside a query: the user is `u`,
+/// their profile is joined as `p` and their trust score as `ts`.
+pub fn ranking_signal_sql() -> String {
+    let score: Vec<String> = CHECKLIST
+        .iter()
+        .map(|(step, weight, required)| format!("CASE WHEN {} >= {} THEN {} ELSE 0 END", step.count_sql(), required, weight))
+        .collect();
+
+    format!(
+        "(COALESCE(ts.score, 0) * {} + ({}) * {})::float8",
+        1.0 - SEARCH_WEIGHT,
+        score.join(" + "),
+        SEARCH_WEIGHT
+    )
+}
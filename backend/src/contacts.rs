@@ -0,0 +1,179 @@
+//! Private tags and notes a user keeps on their connections, for using the
+//! network as a lightweight CRM. Everything here belongs to its owner alone
+//! and is never shown to the contact or anyone else. It's kept when a
+//! connection ends, and shows up again if the two reconnect.
+
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::TagCount;
+use crate::pagination::TOTAL_ESTIMATE_CAP;
+
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// Lookup form of a tag: trimmed, lowercase, with collapsed whitespace.
+/// `None` when nothing is left or it's too long.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+
+    (!tag.is_empty() && tag.chars().count() <= MAX_TAG_LENGTH).then_some(tag)
+}
+
+/// Replaces the owner's tags on a contact. `tags` must already be normalized.
+pub async fn set_tags(pool: &PgPool, owner_id: Uuid, contact_id: Uuid, tags: &[String]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM connection_tags WHERE owner_id = $1 AND contact_id = $2 AND tag <> ALL($3)")
+        .bind(owner_id)
+        .bind(contact_id)
+        .bind(tags)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO connection_tags (owner_id, contact_id, tag)
+         SELECT $1, $2, UNNEST($3::varchar[])
+         ON CONFLICT (owner_id, contact_id, tag) DO NOTHING"
+    )
+        .bind(owner_id)
+        .bind(contact_id)
+        .bind(tags)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await
+}
+
+pub async fn remove_tag(pool: &PgPool, owner_id: Uuid, contact_id: Uuid, tag: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM connection_tags WHERE owner_id = $1 AND contact_id = $2 AND tag = $3")
+        .bind(owner_id)
+        .bind(contact_id)
+        .bind(tag)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Sets or, with `None`, clears the owner's note on a contact.
+pub async fn set_note(pool: &PgPool, owner_id: Uuid, contact_id: Uuid, body: Option<&str>) -> Result<(), sqlx::Error> {
+    match body {
+        Some(body) => {
+            sqlx::query(
+                "INSERT INTO connection_private_notes (owner_id, contact_id, body) VALUES ($1, $2, $3)
+                 ON CONFLICT (owner_id, contact_id) DO UPDATE SET body = EXCLUDED.body, updated_at = NOW()"
+            )
+                .bind(owner_id)
+                .bind(contact_id)
+                .bind(body)
+                .execute(pool)
+                .await?;
+        }
+        None => {
+            sqlx::query("DELETE FROM connection_private_notes WHERE owner_id = $1 AND contact_id = $2")
+                .bind(owner_id)
+                .bind(contact_id)
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The owner's tags on each of their contacts, alphabetically.
+pub async fn tags_by_contact(
+    pool: &PgPool,
+    owner_id: Uuid,
+    contact_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<String>>, sqlx::Error> {
+    let rows: Vec<(Uuid, String)> = sqlx::query_as(
+        "SELECT contact_id, tag FROM connection_tags WHERE owner_id = $1 AND contact_id = ANY($2) ORDER BY tag"
+    )
+        .bind(owner_id)
+        .bind(contact_ids)
+        .fetch_all(pool)
+        .await?;
+
+    let mut tags: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for (contact_id, tag) in rows {
+        tags.entry(contact_id).or_default().push(tag);
+    }
+
+    Ok(tags)
+}
+
+pub async fn notes_by_contact(
+    pool: &PgPool,
+    owner_id: Uuid,
+    contact_ids: &[Uuid],
+) -> Result<HashMap<Uuid, String>, sqlx::Error> {
+    let rows: Vec<(Uuid, String)> = sqlx::query_as(
+        "SELECT contact_id, body FROM connection_private_notes WHERE owner_id = $1 AND contact_id = ANY($2)"
+    )
+        .bind(owner_id)
+        .bind(contact_ids)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// How many current connections carry each of the owner's tags, most used first,
+/// resuming after `after` and fetching up to `limit`.
+pub async fn tag_counts(
+    pool: &PgPool,
+    owner_id: Uuid,
+    after: Option<(i64, String)>,
+    limit: i64,
+) -> Result<Vec<TagCount>, sqlx::Error> {
+    let (after_count, after_tag) = after.unzip();
+
+    sqlx::query_as(
+        "SELECT tag, count FROM (
+             SELECT t.tag, COUNT(*) AS count
+             FROM connection_tags t
+             WHERE t.owner_id = $1
+               AND EXISTS (
+                   SELECT 1 FROM connections c
+                   WHERE c.status = 'accepted'
+                     AND ((c.sender_id = $1 AND c.receiver_id = t.contact_id)
+                          OR (c.sender_id = t.contact_id AND c.receiver_id = $1))
+               )
+             GROUP BY t.tag
+         ) counts
+         WHERE $2::bigint IS NULL OR count < $2 OR (count = $2 AND tag > $3)
+         ORDER BY count DESC, tag
+         LIMIT $4"
+    )
+        .bind(owner_id)
+        .bind(after_count)
+        .bind(after_tag)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// How many distinct tags `owner_id` has on current connections, counted up
+/// to `TOTAL_ESTIMATE_CAP`.
+pub async fn tag_total_estimate(pool: &PgPool, owner_id: Uuid) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (
+             SELECT DISTINCT t.tag
+             FROM connection_tags t
+             WHERE t.owner_id = $1
+               AND EXISTS (
+                   SELECT 1 FROM connections c
+                   WHERE c.status = 'accepted'
+                     AND ((c.sender_id = $1 AND c.receiver_id = t.contact_id)
+                          OR (c.sender_id = t.contact_id AND c.receiver_id = $1))
+               )
+             LIMIT $2
+         ) tags"
+    )
+        .bind(owner_id)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(pool)
+        .await
+}
@@ -0,0 +1,203 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::VerificationTier;
+
+// PKCS#8 v1 wrapper for a raw Ed25519 seed (RFC 8410)
+const ED25519_PKCS8_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20,
+];
+
+const CREDENTIALS_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+const STATUS_LIST_CONTEXT: &str = "https://w3id.org/vc/status-list/2021/v1";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CredentialClaims {
+    pub iss: String,
+    pub sub: String,
+    pub jti: String,
+    pub nbf: i64,
+    pub exp: i64,
+    pub vc: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StatusEntry {
+    pub list_id: i32,
+    pub index: i32,
+}
+
+#[derive(Clone)]
+pub struct CredentialIssuer {
+    did: String,
+    base_url: String,
+    public_key_x: String,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+}
+
+impl CredentialIssuer {
+    pub fn from_seed(seed: &[u8; 32], base_url: &str) -> Self {
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(seed)
+            .expect("Ed25519 seed must be 32 bytes");
+        let public_key_x = URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref());
+
+        let mut pkcs8 = ED25519_PKCS8_PREFIX.to_vec();
+        pkcs8.extend_from_slice(seed);
+
+        let base_url = base_url.trim_end_matches('/').to_string();
+
+        Self {
+            did: did_web_from_url(&base_url),
+            base_url,
+            encoding_key: EncodingKey::from_ed_der(&pkcs8),
+            decoding_key: DecodingKey::from_ed_der(key_pair.public_key().as_ref()),
+            public_key_x,
+        }
+    }
+
+    pub fn did(&self) -> &str {
+        &self.did
+    }
+
+    pub fn key_id(&self) -> String {
+        format!("{}#key-1", self.did)
+    }
+
+    pub fn status_list_url(&self, list_id: i32) -> String {
+        format!("{}/api/credentials/status/{}", self.base_url, list_id)
+    }
+
+    pub fn did_document(&self) -> serde_json::Value {
+        let key_id = self.key_id();
+
+        serde_json::json!({
+            "@context": [
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/suites/jws-2020/v1"
+            ],
+            "id": self.did,
+            "verificationMethod": [{
+                "id": key_id,
+                "type": "JsonWebKey2020",
+                "controller": self.did,
+                "publicKeyJwk": {
+                    "kty": "OKP",
+                    "crv": "Ed25519",
+                    "x": self.public_key_x
+                }
+            }],
+            "assertionMethod": [key_id],
+            "authentication": [key_id]
+        })
+    }
+
+    pub fn issue_tier_credential(
+        &self,
+        credential_id: Uuid,
+        user_id: Uuid,
+        tier: VerificationTier,
+        status: StatusEntry,
+        issued_at: chrono::DateTime<chrono::Utc>,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let status_list_url = self.status_list_url(status.list_id);
+        let subject = format!("urn:uuid:{}", user_id);
+
+        let vc = serde_json::json!({
+            "@context": [CREDENTIALS_CONTEXT, STATUS_LIST_CONTEXT],
+            "id": format!("urn:uuid:{}", credential_id),
+            "type": ["VerifiableCredential", "TrueLinkVerificationCredential"],
+            "issuer": self.did,
+            "issuanceDate": issued_at.to_rfc3339(),
+            "expirationDate": expires_at.to_rfc3339(),
+            "credentialSubject": {
+                "id": subject,
+                "verificationTier": tier.as_str()
+            },
+            "credentialStatus": {
+                "id": format!("{}#{}", status_list_url, status.index),
+                "type": "StatusList2021Entry",
+                "statusPurpose": "revocation",
+                "statusListIndex": status.index.to_string(),
+                "statusListCredential": status_list_url
+            }
+        });
+
+        self.sign(CredentialClaims {
+            iss: self.did.clone(),
+            sub: subject,
+            jti: format!("urn:uuid:{}", credential_id),
+            nbf: issued_at.timestamp(),
+            exp: expires_at.timestamp(),
+            vc,
+        })
+    }
+
+    pub fn issue_status_list_credential(
+        &self,
+        status_list_id: i32,
+        encoded_list: String,
+        issued_at: chrono::DateTime<chrono::Utc>,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let status_list_url = self.status_list_url(status_list_id);
+
+        let vc = serde_json::json!({
+            "@context": [CREDENTIALS_CONTEXT, STATUS_LIST_CONTEXT],
+            "id": status_list_url,
+            "type": ["VerifiableCredential", "StatusList2021Credential"],
+            "issuer": self.did,
+            "issuanceDate": issued_at.to_rfc3339(),
+            "credentialSubject": {
+                "id": format!("{}#list", status_list_url),
+                "type": "StatusList2021",
+                "statusPurpose": "revocation",
+                "encodedList": encoded_list
+            }
+        });
+
+        self.sign(CredentialClaims {
+            iss: self.did.clone(),
+            sub: format!("{}#list", status_list_url),
+            jti: status_list_url,
+            nbf: issued_at.timestamp(),
+            exp: expires_at.timestamp(),
+            vc,
+        })
+    }
+
+    /// Checks the signature, issuer and validity window of a JWT-VC issued by us.
+    pub fn verify(&self, token: &str) -> Result<CredentialClaims, jsonwebtoken::errors::Error> {
+        let mut validation = Validation::new(Algorithm::EdDSA);
+        validation.set_issuer(&[self.did.as_str()]);
+        validation.validate_nbf = true;
+
+        decode::<CredentialClaims>(token, &self.decoding_key, &validation)
+            .map(|token_data| token_data.claims)
+    }
+
+    fn sign(&self, claims: CredentialClaims) -> Result<String, jsonwebtoken::errors::Error> {
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(self.key_id());
+        header.typ = Some("JWT".to_string());
+
+        encode(&header, &claims, &self.encoding_key)
+    }
+}
+
+/// did:web identifier for a base URL, e.g. `http://localhost:8080` -> `did:web:localhost%3A8080`.
+/// The document is served from `/.well-known/did.json`, so only the host is used.
+fn did_web_from_url(base_url: &str) -> String {
+    let host = base_url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .split('/')
+        .next()
+        .unwrap_or_default();
+
+    format!("did:web:{}", host.replace(':', "%3A"))
+}
@@ -0,0 +1,195 @@
+pub mod issuer;
+pub mod status_list;
+
+pub use issuer::CredentialIssuer;
+
+use ring::rand::SystemRandom;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::models::{VerifiableCredential, VerificationTier};
+use issuer::StatusEntry;
+use status_list::STATUS_LIST_SIZE;
+
+const CREDENTIAL_VALIDITY_DAYS: i64 = 365;
+
+/// Random picks to try before looking for what's left on a nearly full list.
+const RANDOM_INDEX_ATTEMPTS: usize = 16;
+
+#[derive(Debug)]
+pub enum CredentialError {
+    Database(sqlx::Error),
+    Signing(jsonwebtoken::errors::Error),
+}
+
+impl std::fmt::Display for CredentialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CredentialError::Database(e) => write!(f, "database error: {}", e),
+            CredentialError::Signing(e) => write!(f, "signing error: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for CredentialError {
+    fn from(e: sqlx::Error) -> Self {
+        CredentialError::Database(e)
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for CredentialError {
+    fn from(e: jsonwebtoken::errors::Error) -> Self {
+        CredentialError::Signing(e)
+    }
+}
+
+/// Grants `tier` to the user and mints a credential for it. Any credential the
+/// user still holds is superseded and revoked in the same transaction.
+pub async fn grant_tier(
+    pool: &PgPool,
+    issuer: &CredentialIssuer,
+    user_id: Uuid,
+    tier: VerificationTier,
+) -> Result<VerifiableCredential, CredentialError> {
+    let mut tx = pool.begin().await?;
+    let credential = mint(&mut tx, issuer, user_id, tier).await?;
+    tx.commit().await?;
+
+    Ok(credential)
+}
+
+/// The user's live credential for `tier`, minting one only when they have
+/// none. Asking again while it's valid hands back the same credential, so
+/// repeated requests don't use up status list entries. The flag says
+/// whether a new one was minted.
+pub async fn ensure_tier(
+    pool: &PgPool,
+    issuer: &CredentialIssuer,
+    user_id: Uuid,
+    tier: VerificationTier,
+) -> Result<(VerifiableCredential, bool), CredentialError> {
+    let mut tx = pool.begin().await?;
+
+    // Concurrent requests from the same user wait here rather than both minting
+    sqlx::query("SELECT 1 FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let current: Option<VerifiableCredential> = sqlx::query_as(
+        "SELECT * FROM verifiable_credentials
+         WHERE user_id = $1 AND verification_tier = $2 AND revoked_at IS NULL AND expires_at > NOW()
+         ORDER BY issued_at DESC
+         LIMIT 1"
+    )
+        .bind(user_id)
+        .bind(tier.as_str())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    if let Some(credential) = current {
+        return Ok((credential, false));
+    }
+
+    let credential = mint(&mut tx, issuer, user_id, tier).await?;
+    tx.commit().await?;
+
+    Ok((credential, true))
+}
+
+async fn mint(
+    conn: &mut PgConnection,
+    issuer: &CredentialIssuer,
+    user_id: Uuid,
+    tier: VerificationTier,
+) -> Result<VerifiableCredential, CredentialError> {
+    sqlx::query("UPDATE users SET verification_tier = $1, updated_at = NOW() WHERE id = $2")
+        .bind(tier.as_str())
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        "UPDATE verifiable_credentials
+         SET revoked_at = NOW()
+         WHERE user_id = $1 AND revoked_at IS NULL"
+    )
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let status = allocate_status_entry(conn).await?;
+
+    let credential_id = Uuid::new_v4();
+    let issued_at = chrono::Utc::now();
+    let expires_at = issued_at + chrono::Duration::days(CREDENTIAL_VALIDITY_DAYS);
+
+    let jwt = issuer.issue_tier_credential(credential_id, user_id, tier, status, issued_at, expires_at)?;
+
+    let credential: VerifiableCredential = sqlx::query_as(
+        "INSERT INTO verifiable_credentials
+            (id, user_id, verification_tier, status_list_id, status_list_index, jwt, issued_at, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+         RETURNING *"
+    )
+        .bind(credential_id)
+        .bind(user_id)
+        .bind(tier.as_str())
+        .bind(status.list_id)
+        .bind(status.index)
+        .bind(&jwt)
+        .bind(issued_at)
+        .bind(expires_at)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok(credential)
+}
+
+/// A free entry at a random index on the list currently filling up.
+async fn allocate_status_entry(conn: &mut PgConnection) -> Result<StatusEntry, sqlx::Error> {
+    let (sequence_value,): (i64,) = sqlx::query_as("SELECT nextval('credential_status_seq')")
+        .fetch_one(&mut *conn)
+        .await?;
+    let list_id = status_list::list_for(sequence_value);
+
+    // Allocations on one list are serialized so two can't pick the same index
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended('status_list:' || $1, 0))")
+        .bind(list_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let rng = SystemRandom::new();
+    for _ in 0..RANDOM_INDEX_ATTEMPTS {
+        let index = status_list::random_index(&rng);
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM verifiable_credentials WHERE status_list_id = $1 AND status_list_index = $2
+             )"
+        )
+            .bind(list_id)
+            .bind(index)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        if !taken {
+            return Ok(StatusEntry { list_id, index });
+        }
+    }
+
+    // Mostly taken: choose among what's left
+    let index: i32 = sqlx::query_scalar(
+        "SELECT candidate FROM generate_series(0, $2 - 1) candidate
+         WHERE NOT EXISTS (
+             SELECT 1 FROM verifiable_credentials WHERE status_list_id = $1 AND status_list_index = candidate
+         )
+         ORDER BY random()
+         LIMIT 1"
+    )
+        .bind(list_id)
+        .bind(STATUS_LIST_SIZE as i32)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok(StatusEntry { list_id, index })
+}
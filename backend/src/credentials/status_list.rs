@@ -0,0 +1,102 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use flate2::{write::GzEncoder, Compression};
+use ring::rand::SecureRandom;
+use std::io::Write;
+
+/// Entries per status list. StatusList2021 requires at least 16KB of bits for herd privacy.
+pub const STATUS_LIST_SIZE: i64 = 131_072;
+
+/// The list a global allocation number goes on. Each list takes at most
+/// `STATUS_LIST_SIZE` allocations, so the one filling up always has a free
+/// index. List ids start at 1.
+pub fn list_for(sequence_value: i64) -> i32 {
+    (sequence_value / STATUS_LIST_SIZE + 1) as i32
+}
+
+/// A uniformly random index on a list. Indices handed out in order would
+/// tell a verifier roughly when a credential was issued, and link the ones
+/// issued together.
+pub fn random_index(rng: &dyn SecureRandom) -> i32 {
+    let mut bytes = [0u8; 4];
+    rng.fill(&mut bytes).expect("system randomness is available");
+
+    // The list size is a power of two, so this is unbiased
+    (u32::from_be_bytes(bytes) % STATUS_LIST_SIZE as u32) as i32
+}
+
+/// GZIP-compressed, base64url-encoded bitstring with the given indices set.
+/// Index 0 is the left-most bit of the first byte.
+pub fn encode(revoked_indices: &[i32]) -> std::io::Result<String> {
+    let mut bits = vec![0u8; (STATUS_LIST_SIZE / 8) as usize];
+
+    for &index in revoked_indices {
+        if index >= 0 && (index as i64) < STATUS_LIST_SIZE {
+            let index = index as usize;
+            bits[index / 8] |= 0b1000_0000 >> (index % 8);
+        }
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&bits)?;
+    let compressed = encoder.finish()?;
+
+    Ok(URL_SAFE_NO_PAD.encode(compressed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use ring::rand::SystemRandom;
+    use std::io::Read;
+
+    fn decode(encoded: &str) -> Vec<u8> {
+        let compressed = URL_SAFE_NO_PAD.decode(encoded).unwrap();
+        let mut bits = Vec::new();
+        GzDecoder::new(&compressed[..]).read_to_end(&mut bits).unwrap();
+        bits
+    }
+
+    #[test]
+    fn an_empty_list_is_all_zeroes() {
+        let bits = decode(&encode(&[]).unwrap());
+
+        assert_eq!(bits.len() as i64, STATUS_LIST_SIZE / 8);
+        assert!(bits.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn sets_bits_left_to_right() {
+        let last = (STATUS_LIST_SIZE - 1) as i32;
+        let bits = decode(&encode(&[0, 9, last]).unwrap());
+
+        assert_eq!(bits[0], 0b1000_0000);
+        assert_eq!(bits[1], 0b0100_0000);
+        assert_eq!(bits[bits.len() - 1], 0b0000_0001);
+        assert_eq!(bits.iter().map(|byte| byte.count_ones()).sum::<u32>(), 3);
+    }
+
+    #[test]
+    fn ignores_indices_off_the_list() {
+        let bits = decode(&encode(&[-1, STATUS_LIST_SIZE as i32]).unwrap());
+
+        assert!(bits.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn each_list_takes_a_full_list_of_allocations() {
+        assert_eq!(list_for(0), 1);
+        assert_eq!(list_for(STATUS_LIST_SIZE - 1), 1);
+        assert_eq!(list_for(STATUS_LIST_SIZE), 2);
+    }
+
+    #[test]
+    fn random_indices_stay_on_the_list() {
+        let rng = SystemRandom::new();
+
+        for _ in 0..1_000 {
+            let index = random_index(&rng);
+            assert!((0..STATUS_LIST_SIZE as i32).contains(&index));
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! One-way follows. Explicit follows live in `follows`; an accepted
+//! connection means both sides follow each other without a row there. The
+//! `follow_edges` view combines the two, so lists and counts never have to
+//! care which kind of relationship they're looking at.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::models::{FollowCounts, FollowEdge, FollowSource};
+use crate::pagination::TOTAL_ESTIMATE_CAP;
+
+/// How `follower_id` follows `followed_id`, if they do.
+pub async fn source(pool: &PgPool, follower_id: Uuid, followed_id: Uuid) -> Result<Option<FollowSource>, sqlx::Error> {
+    sqlx::query_scalar("SELECT source FROM follow_edges WHERE follower_id = $1 AND followed_id = $2")
+        .bind(follower_id)
+        .bind(followed_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Starts following. Returns false if the follow already existed.
+pub async fn follow(pool: &PgPool, follower_id: Uuid, followed_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "INSERT INTO follows (follower_id, followed_id) VALUES ($1, $2)
+         ON CONFLICT (follower_id, followed_id) DO NOTHING"
+    )
+        .bind(follower_id)
+        .bind(followed_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Drops an explicit follow. One implied by a connection stays.
+pub async fn unfollow(pool: &PgPool, follower_id: Uuid, followed_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM follows WHERE follower_id = $1 AND followed_id = $2")
+        .bind(follower_id)
+        .bind(followed_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Removes explicit follows between the two users, in both directions.
+pub async fn remove_between(conn: &mut PgConnection, a: Uuid, b: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "DELETE FROM follows
+         WHERE (follower_id = $1 AND followed_id = $2) OR (follower_id = $2 AND followed_id = $1)"
+    )
+        .bind(a)
+        .bind(b)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn counts(pool: &PgPool, user_id: Uuid) -> Result<FollowCounts, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT COUNT(*) FILTER (WHERE followed_id = $1) AS followers,
+                COUNT(*) FILTER (WHERE follower_id = $1) AS following
+         FROM follow_edges
+         WHERE followed_id = $1 OR follower_id = $1"
+    )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Rows of `follow_edges` whose `column` is `user_id`, counted up to
+/// `TOTAL_ESTIMATE_CAP`: `followed_id` for followers, `follower_id` for following.
+pub async fn total_estimate(pool: &PgPool, user_id: Uuid, column: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM (SELECT 1 FROM follow_edges WHERE {} = $1 LIMIT $2) edges",
+        column
+    ))
+        .bind(user_id)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(pool)
+        .await
+}
+
+/// Where the previous page of a follow list ended.
+pub type FollowCursor = (DateTime<Utc>, Uuid);
+
+/// Everyone following `user_id`, newest first, from after `after`.
+pub async fn followers(
+    pool: &PgPool,
+    user_id: Uuid,
+    after: Option<FollowCursor>,
+    limit: i64,
+) -> Result<Vec<FollowEdge>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT * FROM follow_edges
+         WHERE followed_id = $1
+           AND ($2::timestamptz IS NULL OR (since, follower_id) < ($2, $3))
+         ORDER BY since DESC, follower_id DESC
+         LIMIT $4"
+    )
+        .bind(user_id)
+        .bind(after.map(|(since, _)| since))
+        .bind(after.map(|(_, id)| id))
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Everyone `user_id` follows, newest first, from after `after`.
+pub async fn following(
+    pool: &PgPool,
+    user_id: Uuid,
+    after: Option<FollowCursor>,
+    limit: i64,
+) -> Result<Vec<FollowEdge>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT * FROM follow_edges
+         WHERE follower_id = $1
+           AND ($2::timestamptz IS NULL OR (since, followed_id) < ($2, $3))
+         ORDER BY since DESC, followed_id DESC
+         LIMIT $4"
+    )
+        .bind(user_id)
+        .bind(after.map(|(since, _)| since))
+        .bind(after.map(|(_, id)| id))
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
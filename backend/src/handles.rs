@@ -0,0 +1,246 @@
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::privacy;
+
+pub const MIN_LENGTH: usize = 3;
+pub const MAX_LENGTH: usize = 30;
+
+/// Minimum time between two handle changes by the same user.
+pub const CHANGE_COOLDOWN_DAYS: i64 = 30;
+
+/// How long a retired handle keeps redirecting to its previous owner.
+pub const REDIRECT_GRACE_DAYS: i64 = 90;
+
+/// Handles that would shadow routes, impersonate staff or confuse users.
+const RESERVED: &[&str] = &[
+    "about", "account", "accounts", "admin", "administrator", "api", "app", "auth", "billing",
+    "blog", "careers", "connections", "contact", "dashboard", "developer", "developers", "docs",
+    "edit", "explore", "feed", "help", "home", "in", "jobs", "legal", "login", "logout", "me",
+    "media", "messages", "moderator", "new", "notifications", "official", "privacy", "profile",
+    "register", "root", "search", "security", "settings", "signin", "signup", "staff", "status",
+    "support", "system", "terms", "truelink", "verify", "www",
+];
+
+/// Matched against each hyphen-separated word of a handle, so names that
+/// merely contain one, like Yamashita or Penistone, are left alone.
+const PROFANITY: &[&str] = &[
+    "asshole", "bastard", "bitch", "bollocks", "cocksucker", "cunt", "dickhead", "fuck",
+    "motherfucker", "nigger", "penis", "porn", "pussy", "shit", "slut", "twat", "vagina", "whore",
+];
+
+#[derive(Debug)]
+pub enum HandleError {
+    Length,
+    Characters,
+    Hyphens,
+    Reserved,
+    Profanity,
+}
+
+impl std::fmt::Display for HandleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HandleError::Length => write!(f, "Handle must be {} to {} characters", MIN_LENGTH, MAX_LENGTH),
+            HandleError::Characters => write!(f, "Handle may only contain letters, digits and hyphens"),
+            HandleError::Hyphens => write!(f, "Handle cannot start or end with a hyphen or contain two in a row"),
+            HandleError::Reserved => write!(f, "Handle is reserved"),
+            HandleError::Profanity => write!(f, "Handle is not allowed"),
+        }
+    }
+}
+
+/// Checks a requested handle and returns it lowercased. Handles compare
+/// case-insensitively, so storing them lowercase keeps URLs canonical.
+pub fn validate(handle: &str) -> Result<String, HandleError> {
+    let handle = handle.trim().to_lowercase();
+
+    if handle.len() < MIN_LENGTH || handle.len() > MAX_LENGTH {
+        return Err(HandleError::Length);
+    }
+    if !handle.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-') {
+        return Err(HandleError::Characters);
+    }
+    if handle.starts_with('-') || handle.ends_with('-') || handle.contains("--") {
+        return Err(HandleError::Hyphens);
+    }
+    if RESERVED.contains(&handle.as_str()) {
+        return Err(HandleError::Reserved);
+    }
+
+    if handle.split('-').any(|word| PROFANITY.contains(&word)) {
+        return Err(HandleError::Profanity);
+    }
+
+    Ok(handle)
+}
+
+/// "José María O'Neil" becomes "jose-maria-oneil". Unknown characters
+/// separate words; apostrophes are dropped so names don't split mid-word.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+
+    for c in name.to_lowercase().chars() {
+        let c = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+            'ç' => 'c',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ñ' => 'n',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'ý' | 'ÿ' => 'y',
+            '\'' | '’' => continue,
+            c => c,
+        };
+
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let mut slug: String = slug.chars().take(MAX_LENGTH - 5).collect();
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+/// Whether `handle` is free for `user_id`: nobody else holds it and it isn't
+/// still redirecting for someone else. Users may take back their own old handles.
+pub async fn is_available(conn: &mut PgConnection, handle: &str, user_id: Option<Uuid>) -> Result<bool, sqlx::Error> {
+    let taken: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+             SELECT 1 FROM users WHERE lower(handle) = lower($1) AND id IS DISTINCT FROM $2
+         ) OR EXISTS (
+             SELECT 1 FROM handle_history
+             WHERE lower(handle) = lower($1) AND user_id IS DISTINCT FROM $2 AND expires_at > NOW()
+         )"
+    )
+        .bind(handle)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok(!taken)
+}
+
+/// The slug a default handle starts from: the name as shortened for viewers
+/// who can't see the last name, so "Alice Smith" gets "alice-s" and a hidden
+/// last name never shows up in a URL.
+fn default_slug(full_name: &str) -> String {
+    slugify(&privacy::shorten_name(full_name))
+}
+
+/// A free handle derived from `full_name`: the bare slug when it's valid and
+/// unclaimed, otherwise the slug with a numeric suffix.
+pub async fn suggest(conn: &mut PgConnection, full_name: &str) -> Result<String, sqlx::Error> {
+    let slug = default_slug(full_name);
+    let base = if slug.len() >= MIN_LENGTH && validate(&slug).is_ok() {
+        slug
+    } else {
+        "member".to_string()
+    };
+
+    if is_available(conn, &base, None).await? {
+        return Ok(base);
+    }
+
+    for suffix in 2..100 {
+        let candidate = format!("{}-{}", base, suffix);
+        if is_available(conn, &candidate, None).await? {
+            return Ok(candidate);
+        }
+    }
+
+    // Very common names: fall back to a random suffix
+    loop {
+        let candidate = format!("{}-{}", base, &Uuid::new_v4().simple().to_string()[..4]);
+        if is_available(conn, &candidate, None).await? {
+            return Ok(candidate);
+        }
+    }
+}
+
+pub enum Resolved {
+    Current(Uuid),
+    /// A retired handle still in its grace period, with the owner's current handle.
+    Redirect(String),
+}
+
+/// Finds who a handle points to, following recently retired handles.
+pub async fn resolve(conn: &mut PgConnection, handle: &str) -> Result<Option<Resolved>, sqlx::Error> {
+    let current: Option<Uuid> = sqlx::query_scalar("SELECT id FROM users WHERE lower(handle) = lower($1)")
+        .bind(handle)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    if let Some(user_id) = current {
+        return Ok(Some(Resolved::Current(user_id)));
+    }
+
+    let redirect: Option<String> = sqlx::query_scalar(
+        "SELECT u.handle
+         FROM handle_history h
+         JOIN users u ON u.id = h.user_id
+         WHERE lower(h.handle) = lower($1) AND h.expires_at > NOW()"
+    )
+        .bind(handle)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    Ok(redirect.map(Resolved::Redirect))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_lowercases_and_trims() {
+        assert_eq!(validate("  Alice-Smith ").unwrap(), "alice-smith");
+    }
+
+    #[test]
+    fn validate_rejects_bad_shapes() {
+        assert!(matches!(validate("ab"), Err(HandleError::Length)));
+        assert!(matches!(validate(&"a".repeat(MAX_LENGTH + 1)), Err(HandleError::Length)));
+        assert!(matches!(validate("alice_smith"), Err(HandleError::Characters)));
+        assert!(matches!(validate("-alice"), Err(HandleError::Hyphens)));
+        assert!(matches!(validate("alice--smith"), Err(HandleError::Hyphens)));
+        assert!(matches!(validate("admin"), Err(HandleError::Reserved)));
+    }
+
+    #[test]
+    fn profanity_matches_whole_words_only() {
+        assert!(matches!(validate("shit-happens"), Err(HandleError::Profanity)));
+        assert!(matches!(validate("big-porn"), Err(HandleError::Profanity)));
+
+        for name in ["yamashita", "ken-takeshita", "kinoshita-2", "matsushita", "penistone-fc"] {
+            assert!(validate(name).is_ok(), "{} should be allowed", name);
+        }
+    }
+
+    #[test]
+    fn slugify_folds_accents_and_drops_apostrophes() {
+        assert_eq!(slugify("José María O'Neil"), "jose-maria-oneil");
+        assert_eq!(slugify("  Anne--Marie  "), "anne-marie");
+        assert_eq!(slugify("李"), "");
+    }
+
+    #[test]
+    fn slugify_leaves_room_for_a_suffix() {
+        let slug = slugify(&"abcde ".repeat(10));
+        assert!(slug.len() <= MAX_LENGTH - 5);
+        assert!(!slug.ends_with('-'));
+    }
+
+    #[test]
+    fn default_slug_keeps_only_the_last_initial() {
+        assert_eq!(default_slug("Alice Smith"), "alice-s");
+        assert_eq!(default_slug("José María O'Neil"), "jose-o");
+        assert_eq!(default_slug("Cher"), "cher");
+    }
+}
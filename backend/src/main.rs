@@ -1,11 +1,38 @@
 mod models;
 mod routes;
 mod auth;
+mod blocks;
+mod completeness;
+mod contacts;
+mod credentials;
+mod follows;
+mod handles;
+mod media;
+mod network;
+mod organizations;
+mod pagination;
+mod privacy;
+mod profile_history;
+mod profile_views;
+mod relationships;
+mod request_policy;
+mod resume;
+mod skills;
+mod spam;
+mod storage;
+mod suggestions;
+mod trust;
+mod vcard;
 
 use routes::{create_routes, AppState};
 use auth::service::AuthService;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use credentials::CredentialIssuer;
+use pagination::Cursors;
+use request_policy::RequestPolicy;
 use sqlx::postgres::PgPoolOptions;
 use std::env;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -17,6 +44,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let jwt_secret = env::var("JWT_SECRET")
         .expect("JWT_SECRET must be set");
 
+    let public_base_url = env::var("PUBLIC_BASE_URL")
+        .unwrap_or_else(|_| "http://localhost:8080".to_string());
+
+    let issuer_seed: [u8; 32] = match env::var("VC_ISSUER_SEED") {
+        Ok(seed) => URL_SAFE_NO_PAD
+            .decode(seed.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .expect("VC_ISSUER_SEED must be 32 bytes, base64url encoded"),
+        Err(_) => {
+            println!("⚠️  VC_ISSUER_SEED not set, deriving credential issuer key from JWT_SECRET");
+            let digest = ring::digest::digest(
+                &ring::digest::SHA256,
+                format!("truelink-vc-issuer:{}", jwt_secret).as_bytes(),
+            );
+            digest.as_ref().try_into().expect("SHA-256 digest is 32 bytes")
+        }
+    };
+
     let pool = PgPoolOptions::new()
         .max_connections(5)
         .connect(&database_url)
@@ -38,11 +84,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => println!("⚠️  Could not count users: {}", e),
     }
 
+    let trust_interval_secs: u64 = env::var("TRUST_RECOMPUTE_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(300);
+    trust::spawn_recompute_job(pool.clone(), Duration::from_secs(trust_interval_secs));
+
+    let view_retention_days: i32 = env::var("PROFILE_VIEW_RETENTION_DAYS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(90);
+    profile_views::spawn_rollup_job(pool.clone(), Duration::from_secs(60 * 60), view_retention_days);
+
+    let suggestion_interval_secs: u64 = env::var("SUGGESTIONS_RECOMPUTE_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(60 * 60);
+    suggestions::spawn_recompute_job(pool.clone(), Duration::from_secs(suggestion_interval_secs));
+
+    let request_policy = RequestPolicy::from_env();
+    relationships::spawn_expiry_job(pool.clone(), Duration::from_secs(60 * 60), request_policy.request_expiry);
+
+    let cursors = Cursors::from_secret(&jwt_secret);
     let auth_service = AuthService::new(jwt_secret);
 
+    let issuer = CredentialIssuer::from_seed(&issuer_seed, &public_base_url);
+    println!("🔏 Credential issuer: {}", issuer.did());
+
+    let storage = storage::from_env(&public_base_url);
+
     let app_state = AppState {
         pool,
         auth_service,
+        issuer,
+        storage,
+        public_base_url: public_base_url.trim_end_matches('/').to_string(),
+        request_policy,
+        cursors,
     };
 
     let app = create_routes(app_state);
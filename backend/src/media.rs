@@ -0,0 +1,157 @@
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use std::io::Cursor;
+
+/// Largest accepted upload.
+pub const MAX_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+
+/// Square thumbnail edge lengths produced for every profile picture, largest first.
+pub const THUMBNAIL_SIZES: [u32; 3] = [400, 200, 64];
+
+const MAX_DIMENSION: u32 = 8000;
+const JPEG_QUALITY: u8 = 85;
+
+#[derive(Debug)]
+pub enum MediaError {
+    UnsupportedFormat,
+    Decode(String),
+    Encode(String),
+}
+
+impl std::fmt::Display for MediaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MediaError::UnsupportedFormat => write!(f, "File must be a JPEG, PNG, GIF or WebP image"),
+            MediaError::Decode(e) => write!(f, "Could not read image: {}", e),
+            MediaError::Encode(e) => write!(f, "Could not encode image: {}", e),
+        }
+    }
+}
+
+/// Identifies the image format from its leading magic bytes. The filename and
+/// declared content type are never trusted.
+pub fn sniff_format(bytes: &[u8]) -> Option<ImageFormat> {
+    match bytes {
+        [0xFF, 0xD8, 0xFF, ..] => Some(ImageFormat::Jpeg),
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(ImageFormat::Png),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(ImageFormat::Gif),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(ImageFormat::WebP),
+        _ => None,
+    }
+}
+
+/// Decodes the upload, applies its EXIF orientation, crops it to a centered
+/// square and re-encodes one JPEG per thumbnail size. Re-encoding from pixels
+/// drops every metadata block, so EXIF (including GPS) never reaches storage.
+pub fn process_profile_picture(bytes: &[u8]) -> Result<Vec<(u32, Vec<u8>)>, MediaError> {
+    let format = sniff_format(bytes).ok_or(MediaError::UnsupportedFormat)?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(|e| MediaError::Decode(e.to_string()))?;
+    let orientation = decoder.orientation().map_err(|e| MediaError::Decode(e.to_string()))?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| MediaError::Decode(e.to_string()))?;
+    image.apply_orientation(orientation);
+
+    let edge = image.width().min(image.height());
+    let square = image.crop_imm((image.width() - edge) / 2, (image.height() - edge) / 2, edge, edge);
+
+    THUMBNAIL_SIZES
+        .iter()
+        .map(|&size| {
+            let resized = square.resize_exact(size, size, FilterType::Lanczos3);
+
+            let mut encoded = Vec::new();
+            JpegEncoder::new_with_quality(&mut encoded, JPEG_QUALITY)
+                .encode_image(&DynamicImage::ImageRgb8(resized.to_rgb8()))
+                .map_err(|e| MediaError::Encode(e.to_string()))?;
+
+            Ok((size, encoded))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let pixels = RgbImage::from_fn(width, height, |x, y| Rgb([(x % 256) as u8, (y % 256) as u8, 128]));
+        let mut encoded = Vec::new();
+        JpegEncoder::new(&mut encoded).encode_image(&DynamicImage::ImageRgb8(pixels)).unwrap();
+        encoded
+    }
+
+    /// An APP1 segment whose EXIF carries a GPS IFD with a latitude reference
+    /// and a recognisable GPSAreaInformation string.
+    fn gps_exif_segment() -> Vec<u8> {
+        let mut tiff = b"MM\0\x2a\0\0\0\x08".to_vec();
+        // IFD0: GPSInfo pointer to the GPS IFD at offset 26
+        tiff.extend_from_slice(&[0, 1, 0x88, 0x25, 0, 4, 0, 0, 0, 1, 0, 0, 0, 26, 0, 0, 0, 0]);
+        // GPS IFD: GPSLatitudeRef "N", GPSAreaInformation at offset 56
+        let area = b"ASCII\0\0\0SECRET-LOCATION";
+        tiff.extend_from_slice(&[0, 2, 0, 1, 0, 2, 0, 0, 0, 2, b'N', 0, 0, 0]);
+        tiff.extend_from_slice(&[0, 0x1C, 0, 7, 0, 0, 0, area.len() as u8, 0, 0, 0, 56, 0, 0, 0, 0]);
+        tiff.extend_from_slice(area);
+
+        let mut segment = vec![0xFF, 0xE1];
+        segment.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        segment.extend_from_slice(b"Exif\0\0");
+        segment.extend_from_slice(&tiff);
+        segment
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+
+    #[test]
+    fn sniffs_formats_from_magic_bytes() {
+        assert_eq!(sniff_format(&jpeg(4, 4)), Some(ImageFormat::Jpeg));
+        assert_eq!(sniff_format(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR"), Some(ImageFormat::Png));
+        assert_eq!(sniff_format(b"GIF89a\x01\0\x01\0"), Some(ImageFormat::Gif));
+        assert_eq!(sniff_format(b"RIFF\x24\0\0\0WEBPVP8 "), Some(ImageFormat::WebP));
+        assert_eq!(sniff_format(b"RIFF\x24\0\0\0WAVEfmt "), None);
+        assert_eq!(sniff_format(b""), None);
+    }
+
+    #[test]
+    fn rejects_html_uploaded_as_png() {
+        // The filename and content type never reach this module, so an
+        // "avatar.png" holding markup is judged by its bytes alone
+        let html = b"<!DOCTYPE html><html><body><script>alert(1)</script></body></html>";
+
+        assert_eq!(sniff_format(html), None);
+        assert!(matches!(process_profile_picture(html), Err(MediaError::UnsupportedFormat)));
+    }
+
+    #[test]
+    fn rejects_truncated_images_with_valid_magic() {
+        assert!(matches!(process_profile_picture(b"\x89PNG\r\n\x1a\n"), Err(MediaError::Decode(_))));
+    }
+
+    #[test]
+    fn strips_gps_exif_and_produces_square_thumbnails() {
+        let original = jpeg(600, 300);
+        let mut upload = original[..2].to_vec();
+        upload.extend_from_slice(&gps_exif_segment());
+        upload.extend_from_slice(&original[2..]);
+        assert!(contains(&upload, b"SECRET-LOCATION"));
+
+        let thumbnails = process_profile_picture(&upload).unwrap();
+
+        assert_eq!(thumbnails.iter().map(|(size, _)| *size).collect::<Vec<_>>(), THUMBNAIL_SIZES);
+        for (size, bytes) in &thumbnails {
+            assert!(!contains(bytes, b"Exif\0\0"));
+            assert!(!contains(bytes, b"SECRET-LOCATION"));
+
+            let decoded = image::load_from_memory_with_format(bytes, ImageFormat::Jpeg).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (*size, *size));
+        }
+    }
+}
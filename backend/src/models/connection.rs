@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use validator::Validate;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Connection {
@@ -10,14 +11,78 @@ pub struct Connection {
     pub status: ConnectionStatus,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub note: Option<String>,
+    pub reply: Option<String>,
+    /// When the request was last sent; expiry counts from here
+    pub requested_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type)]
-#[sqlx(type_name = "varchar")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum ConnectionStatus {
     Pending,
     Accepted,
     Rejected,
+    /// Set aside by the receiver without rejecting; the sender isn't told
+    Ignored,
+    /// Left unanswered for too long
+    Expired,
+}
+
+impl Connection {
+    /// The status as `viewer_id` may know it. Senders are never told about a
+    /// rejection or that they were ignored; to them the request is still pending.
+    pub fn status_for(&self, viewer_id: Uuid) -> ConnectionStatus {
+        let hidden = matches!(self.status, ConnectionStatus::Rejected | ConnectionStatus::Ignored);
+        if hidden && self.sender_id == viewer_id {
+            ConnectionStatus::Pending
+        } else {
+            self.status
+        }
+    }
+
+    /// `status_for`, once `expiry` is taken into account: anything that still
+    /// looks pending past it shows as expired. Rejected and ignored requests
+    /// are never expired for real, but their sender sees them lapse on the
+    /// same schedule as a request nobody answered.
+    pub fn status_for_as_of(&self, viewer_id: Uuid, expiry: chrono::Duration) -> ConnectionStatus {
+        let status = self.status_for(viewer_id);
+        if status == ConnectionStatus::Pending && self.lapsed(expiry) {
+            ConnectionStatus::Expired
+        } else {
+            status
+        }
+    }
+
+    /// Whether the request was last sent more than `expiry` ago.
+    pub fn lapsed(&self, expiry: chrono::Duration) -> bool {
+        self.requested_at
+            .checked_add_signed(expiry)
+            .is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+    }
+}
+
+impl ConnectionStatus {
+    /// The moves a connection may make. A pending request is answered once,
+    /// though an ignored one can still be answered later. Pending requests
+    /// expire in time. Rejected and expired requests, and ignored ones past
+    /// the expiry, may be sent again once the pair's cooldown is over; sent
+    /// again during the cooldown, they're shelved as rejected. Accepted
+    /// connections only end by being removed, never by changing status.
+    pub fn can_transition_to(self, next: ConnectionStatus) -> bool {
+        matches!(
+            (self, next),
+            (ConnectionStatus::Pending, ConnectionStatus::Accepted)
+                | (ConnectionStatus::Pending, ConnectionStatus::Rejected)
+                | (ConnectionStatus::Pending, ConnectionStatus::Ignored)
+                | (ConnectionStatus::Ignored, ConnectionStatus::Accepted)
+                | (ConnectionStatus::Ignored, ConnectionStatus::Rejected)
+                | (ConnectionStatus::Pending, ConnectionStatus::Expired)
+                | (ConnectionStatus::Rejected | ConnectionStatus::Expired | ConnectionStatus::Ignored, ConnectionStatus::Pending)
+                | (ConnectionStatus::Expired, ConnectionStatus::Rejected)
+        )
+    }
 }
 
 impl std::fmt::Display for ConnectionStatus {
@@ -26,18 +91,45 @@ impl std::fmt::Display for ConnectionStatus {
             ConnectionStatus::Pending => write!(f, "pending"),
             ConnectionStatus::Accepted => write!(f, "accepted"),
             ConnectionStatus::Rejected => write!(f, "rejected"),
+            ConnectionStatus::Ignored => write!(f, "ignored"),
+            ConnectionStatus::Expired => write!(f, "expired"),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct ConnectionRequest {
     pub receiver_id: Uuid,
+
+    /// A personal note for the receiver, e.g. "We met at RustConf"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 300, message = "Note must be at most 300 characters"))]
+    pub note: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UpdateConnectionRequest {
     pub status: ConnectionStatus,
+
+    /// A reply to the sender's note; only when accepting
+    #[serde(default)]
+    #[validate(length(max = 300, message = "Reply must be at most 300 characters"))]
+    pub reply: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkRequestAction {
+    Accept,
+    Ignore,
+}
+
+/// Accepts or ignores several incoming requests at once.
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkRequestUpdate {
+    pub action: BulkRequestAction,
+    #[validate(length(min = 1, max = 100, message = "Between 1 and 100 requests at a time"))]
+    pub connection_ids: Vec<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -49,4 +141,114 @@ pub struct ConnectionWithUser {
     pub profile_picture_url: Option<String>,
     pub status: ConnectionStatus,
     pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Replaces the caller's tags on one connection.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetConnectionTagsRequest {
+    #[validate(length(max = 20, message = "At most 20 tags per connection"))]
+    pub tags: Vec<String>,
+}
+
+/// Sets the caller's private note on one connection; empty clears it.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetPrivateNoteRequest {
+    #[validate(length(max = 5000, message = "Note must be at most 5000 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ConnectionStatus::*;
+
+    const ALL: [ConnectionStatus; 5] = [Pending, Accepted, Rejected, Ignored, Expired];
+
+    #[test]
+    fn pending_requests_can_be_answered_or_expire() {
+        for next in [Accepted, Rejected, Ignored, Expired] {
+            assert!(Pending.can_transition_to(next), "pending -> {}", next);
+        }
+    }
+
+    #[test]
+    fn ignored_requests_can_still_be_answered() {
+        assert!(Ignored.can_transition_to(Accepted));
+        assert!(Ignored.can_transition_to(Rejected));
+    }
+
+    #[test]
+    fn only_pending_requests_expire() {
+        assert!(Pending.can_transition_to(Expired));
+        for status in [Accepted, Rejected, Ignored] {
+            assert!(!status.can_transition_to(Expired), "{} -> expired", status);
+        }
+    }
+
+    #[test]
+    fn unanswered_requests_can_be_sent_again() {
+        assert!(Rejected.can_transition_to(Pending));
+        assert!(Expired.can_transition_to(Pending));
+        assert!(Ignored.can_transition_to(Pending));
+        assert!(Expired.can_transition_to(Rejected));
+        assert!(!Rejected.can_transition_to(Accepted));
+        assert!(!Expired.can_transition_to(Accepted));
+    }
+
+    fn request(status: ConnectionStatus, sent_days_ago: i64) -> Connection {
+        let now = chrono::Utc::now();
+        Connection {
+            id: Uuid::new_v4(),
+            sender_id: Uuid::from_u128(1),
+            receiver_id: Uuid::from_u128(2),
+            status,
+            created_at: now,
+            updated_at: now,
+            note: None,
+            reply: None,
+            requested_at: now - chrono::Duration::days(sent_days_ago),
+        }
+    }
+
+    #[test]
+    fn senders_never_see_rejections_or_ignores() {
+        let sender = Uuid::from_u128(1);
+        let receiver = Uuid::from_u128(2);
+        for status in [Rejected, Ignored] {
+            assert_eq!(request(status, 1).status_for(sender), Pending);
+            assert_eq!(request(status, 1).status_for(receiver), status);
+        }
+    }
+
+    #[test]
+    fn hidden_answers_lapse_like_unanswered_requests() {
+        let sender = Uuid::from_u128(1);
+        let expiry = chrono::Duration::days(90);
+        for status in [Pending, Rejected, Ignored] {
+            assert_eq!(request(status, 10).status_for_as_of(sender, expiry), Pending);
+            assert_eq!(request(status, 100).status_for_as_of(sender, expiry), Expired);
+        }
+        assert_eq!(request(Rejected, 100).status_for_as_of(Uuid::from_u128(2), expiry), Rejected);
+    }
+
+    #[test]
+    fn accepted_connections_never_change_status() {
+        for next in ALL {
+            assert!(!Accepted.can_transition_to(next), "accepted -> {}", next);
+        }
+    }
+
+    #[test]
+    fn no_status_moves_to_itself() {
+        for status in ALL {
+            assert!(!status.can_transition_to(status), "{} -> {}", status, status);
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct VerifiableCredential {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub verification_tier: String,
+    pub status_list_id: i32,
+    pub status_list_index: i32,
+    pub jwt: String,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyCredentialRequest {
+    pub credential: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyCredentialResponse {
+    pub valid: bool,
+    pub revoked: bool,
+    pub subject: Option<Uuid>,
+    pub verification_tier: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,219 @@
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Position {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub company_name: String,
+    pub title: String,
+    pub start_year: i32,
+    pub start_month: i32,
+    pub end_year: Option<i32>,
+    pub end_month: Option<i32>,
+    pub is_current: bool,
+    pub description: Option<String>,
+    pub sort_order: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_position_dates", skip_on_field_errors = false))]
+pub struct PositionRequest {
+    #[validate(length(min = 1, max = 200, message = "Company must be 1-200 characters"))]
+    pub company_name: String,
+
+    #[validate(length(min = 1, max = 200, message = "Title must be 1-200 characters"))]
+    pub title: String,
+
+    #[validate(range(min = 1900, max = 2100, message = "Start year is out of range"))]
+    pub start_year: i32,
+
+    #[validate(range(min = 1, max = 12, message = "Start month must be 1-12"))]
+    pub start_month: i32,
+
+    #[validate(range(min = 1900, max = 2100, message = "End year is out of range"))]
+    pub end_year: Option<i32>,
+
+    #[validate(range(min = 1, max = 12, message = "End month must be 1-12"))]
+    pub end_month: Option<i32>,
+
+    #[serde(default)]
+    pub is_current: bool,
+
+    #[validate(length(max = 2000, message = "Description must be at most 2000 characters"))]
+    pub description: Option<String>,
+}
+
+impl PositionRequest {
+    /// The request with surrounding whitespace gone, so validation sees what
+    /// will be stored: a company of "   " is empty, not three characters.
+    pub fn trimmed(self) -> Self {
+        Self {
+            company_name: self.company_name.trim().to_string(),
+            title: self.title.trim().to_string(),
+            description: trim_optional(self.description),
+            ..self
+        }
+    }
+}
+
+fn validate_position_dates(position: &PositionRequest) -> Result<(), ValidationError> {
+    let today = chrono::Utc::now().date_naive();
+    let now = (today.year(), today.month() as i32);
+    let start = (position.start_year, position.start_month);
+
+    if start > now {
+        return Err(date_error("Start date cannot be in the future"));
+    }
+
+    match (position.is_current, position.end_year, position.end_month) {
+        (true, None, None) => Ok(()),
+        (true, _, _) => Err(date_error("A current position cannot have an end date")),
+        (false, Some(end_year), Some(end_month)) => {
+            if (end_year, end_month) < start {
+                Err(date_error("End date must not be before start date"))
+            } else if (end_year, end_month) > now {
+                Err(date_error("End date cannot be in the future"))
+            } else {
+                Ok(())
+            }
+        }
+        (false, _, _) => Err(date_error("End year and month are required unless the position is current")),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Education {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub school_name: String,
+    pub degree: Option<String>,
+    pub field_of_study: Option<String>,
+    pub start_year: Option<i32>,
+    pub end_year: Option<i32>,
+    pub sort_order: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_education_years", skip_on_field_errors = false))]
+pub struct EducationRequest {
+    #[validate(length(min = 1, max = 200, message = "School must be 1-200 characters"))]
+    pub school_name: String,
+
+    #[validate(length(max = 200, message = "Degree must be at most 200 characters"))]
+    pub degree: Option<String>,
+
+    #[validate(length(max = 200, message = "Field of study must be at most 200 characters"))]
+    pub field_of_study: Option<String>,
+
+    #[validate(range(min = 1900, max = 2100, message = "Start year is out of range"))]
+    pub start_year: Option<i32>,
+
+    #[validate(range(min = 1900, max = 2100, message = "End year is out of range"))]
+    pub end_year: Option<i32>,
+}
+
+impl EducationRequest {
+    /// The request with surrounding whitespace gone; see `PositionRequest::trimmed`.
+    pub fn trimmed(self) -> Self {
+        Self {
+            school_name: self.school_name.trim().to_string(),
+            degree: trim_optional(self.degree),
+            field_of_study: trim_optional(self.field_of_study),
+            ..self
+        }
+    }
+}
+
+fn validate_education_years(education: &EducationRequest) -> Result<(), ValidationError> {
+    match (education.start_year, education.end_year) {
+        (Some(start), Some(end)) if end < start => Err(date_error("End year must not be before start year")),
+        (Some(start), _) if start > chrono::Utc::now().year() => {
+            Err(date_error("Start year cannot be in the future"))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn trim_optional(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn date_error(message: &'static str) -> ValidationError {
+    let mut error = ValidationError::new("dates");
+    error.message = Some(message.into());
+    error
+}
+
+/// New display order: every entry id, first to last.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReorderRequest {
+    pub ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Organization {
+    pub id: Uuid,
+    pub kind: String,
+    pub name: String,
+    pub normalized_name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(company_name: &str, title: &str) -> PositionRequest {
+        PositionRequest {
+            company_name: company_name.to_string(),
+            title: title.to_string(),
+            start_year: 2020,
+            start_month: 1,
+            end_year: None,
+            end_month: None,
+            is_current: true,
+            description: Some("  ".to_string()),
+        }
+    }
+
+    #[test]
+    fn blank_company_or_title_is_rejected_once_trimmed() {
+        assert!(position("   ", "Engineer").trimmed().validate().is_err());
+        assert!(position("Acme", "\t").trimmed().validate().is_err());
+    }
+
+    #[test]
+    fn trims_names_and_drops_blank_descriptions() {
+        let position = position("  Acme  ", " Engineer ").trimmed();
+
+        assert!(position.validate().is_ok());
+        assert_eq!(position.company_name, "Acme");
+        assert_eq!(position.title, "Engineer");
+        assert_eq!(position.description, None);
+    }
+
+    #[test]
+    fn blank_school_is_rejected_once_trimmed() {
+        let education = EducationRequest {
+            school_name: "  ".to_string(),
+            degree: Some(" BSc ".to_string()),
+            field_of_study: None,
+            start_year: None,
+            end_year: None,
+        }
+        .trimmed();
+
+        assert!(education.validate().is_err());
+        assert_eq!(education.degree.as_deref(), Some("BSc"));
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::UserCard;
+
+/// Why one user follows another. Connected users follow each other without
+/// an explicit follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum FollowSource {
+    Follow,
+    Connection,
+}
+
+/// A row of the `follow_edges` view.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct FollowEdge {
+    pub follower_id: Uuid,
+    pub followed_id: Uuid,
+    pub since: chrono::DateTime<chrono::Utc>,
+    pub source: FollowSource,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, FromRow)]
+pub struct FollowCounts {
+    pub followers: i64,
+    pub following: i64,
+}
+
+/// One entry in a follower or following list.
+#[derive(Debug, Serialize)]
+pub struct FollowListEntry {
+    pub user: UserCard,
+    pub since: chrono::DateTime<chrono::Utc>,
+    pub source: FollowSource,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatorModeRequest {
+    pub enabled: bool,
+}
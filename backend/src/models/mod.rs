@@ -1,7 +1,31 @@
 pub mod user;
 pub mod profile;
+pub mod privacy;
+pub mod profile_view;
+pub mod profile_revision;
 pub mod connection;
+pub mod follow;
+pub mod credential;
+pub mod experience;
+pub mod skill;
+pub mod recommendation;
+pub mod trust;
 
-pub use user::{User, CreateUserRequest, LoginRequest, AuthResponse};
-pub use profile::{UserProfile, UpdateProfileRequest};
-pub use connection::{Connection, ConnectionStatus, ConnectionRequest, UpdateConnectionRequest, ConnectionWithUser};
\ No newline at end of file
+pub use user::{User, CreateUserRequest, LoginRequest, AuthResponse, VerificationTier, UpdateHandleRequest};
+pub use profile::{UserProfile, UpdateProfileRequest, ProfileView, ProfileDetails};
+pub use privacy::{Audience, PrivacySettings, UpdatePrivacySettingsRequest, UserCard};
+pub use profile_view::{ProfileViewer, StatsPeriod, ViewStat};
+pub use profile_revision::{ProfileFieldChange, ProfileRevision};
+pub use connection::{
+    BulkRequestAction, BulkRequestUpdate, Connection, ConnectionRequest, ConnectionStatus, UpdateConnectionRequest,
+    SetConnectionTagsRequest, SetPrivateNoteRequest, TagCount,
+};
+pub use follow::{CreatorModeRequest, FollowCounts, FollowEdge, FollowListEntry, FollowSource};
+pub use credential::{VerifiableCredential, VerifyCredentialRequest, VerifyCredentialResponse};
+pub use trust::{TrustScore, Vouch};
+pub use experience::{Position, PositionRequest, Education, EducationRequest, ReorderRequest, Organization};
+pub use skill::{Skill, AddSkillRequest, RankedSkill, Endorsement, Endorser, MergeSkillRequest};
+pub use recommendation::{
+    Recommendation, RecommendationStatus, RecommendationRevision,
+    RequestRecommendationRequest, WriteRecommendationRequest, EditRecommendationRequest,
+};
\ No newline at end of file
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Who may see a piece of a user's data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Audience {
+    Public,
+    Connections,
+    /// Connections and their connections
+    SecondDegree,
+    OnlyMe,
+}
+
+impl Audience {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Audience::Public => "public",
+            Audience::Connections => "connections",
+            Audience::SecondDegree => "second_degree",
+            Audience::OnlyMe => "only_me",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PrivacySettings {
+    pub user_id: Uuid,
+    pub email: Audience,
+    pub profile_fields: Audience,
+    pub connection_list: Audience,
+    pub last_name: Audience,
+    /// Profile views this user makes are recorded anonymously.
+    pub browse_privately: bool,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl PrivacySettings {
+    /// Settings for users without a row yet; matches the column defaults.
+    pub fn defaults(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            email: Audience::Connections,
+            profile_fields: Audience::Public,
+            connection_list: Audience::Connections,
+            last_name: Audience::Public,
+            browse_privately: false,
+            updated_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// Partial update; omitted fields keep their current value.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdatePrivacySettingsRequest {
+    pub email: Option<Audience>,
+    pub profile_fields: Option<Audience>,
+    pub connection_list: Option<Audience>,
+    pub last_name: Option<Audience>,
+    pub browse_privately: Option<bool>,
+}
+
+/// How any endpoint presents another user. Built only by
+/// `privacy::user_cards`, which leaves out whatever the viewer may not see.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserCard {
+    pub id: Uuid,
+    pub handle: String,
+    pub full_name: String,
+    pub profile_picture_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headline: Option<String>,
+}
@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::models::{Education, FollowCounts, Position, UserCard};
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct UserProfile {
-    pub id: i32,
-    pub user_id: i32,
+    pub user_id: Uuid,
     pub headline: Option<String>,
     pub summary: Option<String>,
     pub location: Option<String>,
@@ -13,10 +16,57 @@ pub struct UserProfile {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UpdateProfileRequest {
+    #[validate(length(max = 120, message = "Headline must be at most 120 characters"))]
     pub headline: Option<String>,
+
+    #[validate(length(max = 2000, message = "Summary must be at most 2000 characters"))]
+    pub summary: Option<String>,
+
+    #[validate(length(max = 100, message = "Location must be at most 100 characters"))]
+    pub location: Option<String>,
+
+    #[validate(
+        custom = "validate_website",
+        length(max = 255, message = "Website must be at most 255 characters")
+    )]
+    pub website: Option<String>,
+}
+
+fn validate_website(website: &str) -> Result<(), ValidationError> {
+    let has_web_scheme = website.starts_with("https://") || website.starts_with("http://");
+
+    if !has_web_scheme || !validator::validate_url(website) {
+        let mut error = ValidationError::new("url");
+        error.message = Some("Website must be a valid http(s) URL".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// Another user's profile as the viewer may see it. Profile fields the
+/// viewer isn't allowed to see are left empty.
+#[derive(Debug, Serialize)]
+pub struct ProfileView {
+    #[serde(flatten)]
+    pub user: UserCard,
+    pub verification_tier: Option<String>,
     pub summary: Option<String>,
     pub location: Option<String>,
     pub website: Option<String>,
+    /// Creators lead with Follow rather than Connect
+    pub creator_mode: bool,
+    /// Hidden along with the follower lists by the connection list setting
+    pub follow_counts: Option<FollowCounts>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileDetails {
+    #[serde(flatten)]
+    pub profile: ProfileView,
+    pub positions: Vec<Position>,
+    pub education: Vec<Education>,
 }
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// One profile field before and after a change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileFieldChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// The profile fields as they stood after a change, and what the change was.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ProfileRevision {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub version: i32,
+    pub actor_id: Option<Uuid>,
+    pub headline: Option<String>,
+    pub summary: Option<String>,
+    pub location: Option<String>,
+    pub website: Option<String>,
+    pub changes: sqlx::types::Json<Vec<ProfileFieldChange>>,
+    pub reverted_to: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::UserCard;
+
+/// One entry in "who viewed my profile". Anonymous viewers come with a
+/// description instead of a card.
+#[derive(Debug, Serialize)]
+pub struct ProfileViewer {
+    pub viewed_at: chrono::DateTime<chrono::Utc>,
+    pub anonymous: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viewer: Option<UserCard>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsPeriod {
+    Day,
+    Week,
+}
+
+impl StatsPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsPeriod::Day => "day",
+            StatsPeriod::Week => "week",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ViewStat {
+    pub period_start: chrono::NaiveDate,
+    pub views: i64,
+    pub anonymous_views: i64,
+}
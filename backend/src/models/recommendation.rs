@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Recommendation {
+    pub id: Uuid,
+    pub author_id: Uuid,
+    pub recipient_id: Uuid,
+    pub status: RecommendationStatus,
+    pub request_message: Option<String>,
+    pub relationship: Option<RecommendationRelationship>,
+    pub body: Option<String>,
+    pub version: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationStatus {
+    Requested,
+    PendingApproval,
+    Visible,
+    Hidden,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationRelationship {
+    ManagedDirectly,
+    ReportedDirectly,
+    SeniorToButNotManager,
+    JuniorToButNotManager,
+    SameTeam,
+    DifferentTeams,
+    Client,
+    Mentor,
+    StudiedTogether,
+    TaughtThem,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct RequestRecommendationRequest {
+    pub author_id: Uuid,
+
+    #[validate(length(max = 500, message = "Message must be at most 500 characters"))]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct WriteRecommendationRequest {
+    pub recipient_id: Uuid,
+    pub relationship: RecommendationRelationship,
+
+    #[validate(length(min = 20, max = 3000, message = "Recommendation must be 20-3000 characters"))]
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct EditRecommendationRequest {
+    pub relationship: RecommendationRelationship,
+
+    #[validate(length(min = 20, max = 3000, message = "Recommendation must be 20-3000 characters"))]
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct RecommendationRevision {
+    pub id: Uuid,
+    pub recommendation_id: Uuid,
+    pub version: i32,
+    pub relationship: RecommendationRelationship,
+    pub body: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
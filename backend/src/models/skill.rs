@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::UserCard;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Skill {
+    pub id: Uuid,
+    pub name: String,
+    pub normalized_name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct AddSkillRequest {
+    #[validate(length(min = 1, max = 100, message = "Skill name must be 1-100 characters"))]
+    pub name: String,
+}
+
+/// Folds the skill in the path into `into`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeSkillRequest {
+    pub into: Uuid,
+}
+
+/// A user's skill with its endorsement ranking.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RankedSkill {
+    pub skill_id: Uuid,
+    pub name: String,
+    pub endorsement_count: i64,
+    pub weighted_score: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Endorsement {
+    pub id: Uuid,
+    pub endorser_id: Uuid,
+    pub user_id: Uuid,
+    pub skill_id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Endorser {
+    pub endorsement_id: Uuid,
+    pub endorser: UserCard,
+    pub verification_tier: Option<String>,
+    pub endorsed_at: chrono::DateTime<chrono::Utc>,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TrustScore {
+    pub user_id: Uuid,
+    pub score: f64,
+    pub tier_component: f64,
+    pub age_component: f64,
+    pub vouch_component: f64,
+    pub propagation_component: f64,
+    pub computed_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Vouch {
+    pub voucher_id: Uuid,
+    pub vouchee_id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
@@ -8,6 +8,7 @@ pub struct User {
     pub id: Uuid,
     pub email: String,
     pub full_name: String,
+    pub handle: String,
     pub profile_picture_url: Option<String>,
     pub email_verified: bool,
     pub verification_tier: String,
@@ -40,6 +41,12 @@ pub struct LoginRequest {
 
     pub password: String,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateHandleRequest {
+    pub handle: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AuthResponse {
     pub token: String,
@@ -55,3 +62,36 @@ pub struct AuthMethod {
     pub password_hash: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationTier {
+    Standard,
+    EmailVerified,
+    IdentityVerified,
+}
+
+impl VerificationTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerificationTier::Standard => "standard",
+            VerificationTier::EmailVerified => "email_verified",
+            VerificationTier::IdentityVerified => "identity_verified",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "standard" => Some(VerificationTier::Standard),
+            "email_verified" => Some(VerificationTier::EmailVerified),
+            "identity_verified" => Some(VerificationTier::IdentityVerified),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for VerificationTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
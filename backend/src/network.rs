@@ -0,0 +1,412 @@
+//! Distances in the connection graph: mutual connections, degrees up to the
+//! third, and the shortest path of people between two users. Privacy
+//! checks use the same degrees, cut off at the second.
+//!
+//! Searches expand whole frontiers at once with one query per level, from
+//! whichever end has the smaller frontier, so a third-degree lookup costs at
+//! most three indexed queries however large the graph is.
+
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::blocks;
+use crate::privacy::{Field, ViewerAccess};
+
+/// Degrees past this are reported as out of network.
+pub const MAX_DISTANCE: usize = 3;
+
+/// Shortest paths considered when looking for one the viewer may see.
+const MAX_CANDIDATE_PATHS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum NetworkDegree {
+    #[serde(rename = "self")]
+    Own,
+    #[serde(rename = "1st")]
+    First,
+    #[serde(rename = "2nd")]
+    Second,
+    #[serde(rename = "3rd")]
+    Third,
+    #[serde(rename = "out_of_network")]
+    OutOfNetwork,
+}
+
+impl NetworkDegree {
+    fn from_distance(distance: usize) -> Self {
+        match distance {
+            0 => NetworkDegree::Own,
+            1 => NetworkDegree::First,
+            2 => NetworkDegree::Second,
+            3 => NetworkDegree::Third,
+            _ => NetworkDegree::OutOfNetwork,
+        }
+    }
+}
+
+/// Accepted connections of every user in `ids`, leaving out `excluded`.
+async fn neighbors(
+    pool: &PgPool,
+    ids: &[Uuid],
+    excluded: &HashSet<Uuid>,
+) -> Result<HashMap<Uuid, Vec<Uuid>>, sqlx::Error> {
+    let edges: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        "SELECT sender_id, receiver_id FROM connections WHERE status = 'accepted' AND sender_id = ANY($1)
+         UNION ALL
+         SELECT receiver_id, sender_id FROM connections WHERE status = 'accepted' AND receiver_id = ANY($1)"
+    )
+        .bind(ids)
+        .fetch_all(pool)
+        .await?;
+
+    let mut adjacency: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (from, to) in edges {
+        if !excluded.contains(&to) {
+            adjacency.entry(from).or_default().push(to);
+        }
+    }
+
+    Ok(adjacency)
+}
+
+/// Everyone blocked by `user_id` or blocking them; the graph is walked as
+/// though they weren't there.
+async fn blocked_by_either(pool: &PgPool, user_id: Uuid) -> Result<HashSet<Uuid>, sqlx::Error> {
+    let ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT blocked_id FROM user_blocks WHERE blocker_id = $1
+         UNION
+         SELECT blocker_id FROM user_blocks WHERE blocked_id = $1"
+    )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(ids.into_iter().collect())
+}
+
+/// Shared accepted connections of `a` and `b`.
+pub async fn mutual_connections(pool: &PgPool, a: Uuid, b: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+    let excluded = blocked_by_either(pool, a).await?;
+    let adjacency = neighbors(pool, &[a, b], &excluded).await?;
+
+    let of_b: HashSet<Uuid> = adjacency.get(&b).into_iter().flatten().copied().collect();
+    let mut mutual: Vec<Uuid> = adjacency
+        .get(&a)
+        .into_iter()
+        .flatten()
+        .filter(|id| of_b.contains(id))
+        .copied()
+        .collect();
+    mutual.sort();
+
+    Ok(mutual)
+}
+
+/// One end of a bidirectional search: each reached user with their
+/// distance from the root and every neighbour one step closer to it.
+struct Side {
+    reached: HashMap<Uuid, (usize, Vec<Uuid>)>,
+    frontier: Vec<Uuid>,
+    depth: usize,
+}
+
+impl Side {
+    fn new(root: Uuid) -> Self {
+        Self {
+            reached: HashMap::from([(root, (0, Vec::new()))]),
+            frontier: vec![root],
+            depth: 0,
+        }
+    }
+
+    async fn expand(&mut self, pool: &PgPool, excluded: &HashSet<Uuid>) -> Result<Vec<Uuid>, sqlx::Error> {
+        let adjacency = neighbors(pool, &self.frontier, excluded).await?;
+        self.depth += 1;
+
+        let mut next = Vec::new();
+        for (from, tos) in adjacency {
+            for to in tos {
+                match self.reached.get_mut(&to) {
+                    Some((depth, parents)) if *depth == self.depth => parents.push(from),
+                    Some(_) => {}
+                    None => {
+                        self.reached.insert(to, (self.depth, vec![from]));
+                        next.push(to);
+                    }
+                }
+            }
+        }
+
+        self.frontier = next.clone();
+        Ok(next)
+    }
+
+    /// Every shortest path from the root to `id`, root first.
+    fn paths_to(&self, id: Uuid, limit: usize) -> Vec<Vec<Uuid>> {
+        let Some((_, parents)) = self.reached.get(&id) else {
+            return Vec::new();
+        };
+
+        if parents.is_empty() {
+            return vec![vec![id]];
+        }
+
+        let mut paths = Vec::new();
+        for &parent in parents {
+            for mut path in self.paths_to(parent, limit - paths.len()) {
+                path.push(id);
+                paths.push(path);
+                if paths.len() == limit {
+                    return paths;
+                }
+            }
+        }
+        paths
+    }
+}
+
+/// How far `to` is from `from`, with every shortest path between them
+/// (both ends included), up to `MAX_CANDIDATE_PATHS` of them.
+async fn shortest_paths(
+    pool: &PgPool,
+    from: Uuid,
+    to: Uuid,
+    excluded: &HashSet<Uuid>,
+) -> Result<Option<(usize, Vec<Vec<Uuid>>)>, sqlx::Error> {
+    let mut forward = Side::new(from);
+    let mut backward = Side::new(to);
+
+    while forward.depth + backward.depth < MAX_DISTANCE {
+        let (near, far) = if forward.frontier.len() <= backward.frontier.len() {
+            (&mut forward, &backward)
+        } else {
+            (&mut backward, &forward)
+        };
+
+        if near.frontier.is_empty() {
+            return Ok(None);
+        }
+
+        let reached = near.expand(pool, excluded).await?;
+
+        // Where the two searches meet, and how long the path through there is
+        let meetings: Vec<(Uuid, usize)> = reached
+            .into_iter()
+            .filter_map(|id| far.reached.get(&id).map(|(depth, _)| (id, near.depth + depth)))
+            .collect();
+
+        let Some(distance) = meetings.iter().map(|(_, distance)| *distance).min() else {
+            continue;
+        };
+
+        let mut middles: Vec<Uuid> = meetings
+            .into_iter()
+            .filter(|(_, d)| *d == distance)
+            .map(|(id, _)| id)
+            .collect();
+        middles.sort();
+
+        let mut paths = Vec::new();
+        'middles: for middle in middles {
+            for head in forward.paths_to(middle, MAX_CANDIDATE_PATHS) {
+                for tail in backward.paths_to(middle, MAX_CANDIDATE_PATHS) {
+                    let mut path = head.clone();
+                    path.extend(tail.into_iter().rev().skip(1));
+                    paths.push(path);
+                    if paths.len() == MAX_CANDIDATE_PATHS {
+                        break 'middles;
+                    }
+                }
+            }
+        }
+
+        return Ok(Some((distance, paths)));
+    }
+
+    Ok(None)
+}
+
+/// Where `target_id` sits in `viewer_id`'s network, and the people in
+/// between on one shortest path. The path is `None` when every shortest
+/// path runs through a connection the viewer isn't allowed to see.
+pub async fn degree_with_path(
+    pool: &PgPool,
+    viewer_id: Uuid,
+    target_id: Uuid,
+) -> Result<(NetworkDegree, Option<Vec<Uuid>>), sqlx::Error> {
+    if viewer_id == target_id {
+        return Ok((NetworkDegree::Own, Some(Vec::new())));
+    }
+
+    let excluded = blocked_by_either(pool, viewer_id).await?;
+
+    let Some((distance, paths)) = shortest_paths(pool, viewer_id, target_id, &excluded).await? else {
+        return Ok((NetworkDegree::OutOfNetwork, None));
+    };
+
+    let people: Vec<Uuid> = paths.iter().flatten().copied().collect::<HashSet<_>>().into_iter().collect();
+    let access = ViewerAccess::load(pool, Some(viewer_id), &people).await?;
+
+    let path = paths
+        .into_iter()
+        .find(|path| path.windows(2).all(|edge| edge_visible(&access, viewer_id, edge[0], edge[1])))
+        .map(|path| path[1..path.len() - 1].to_vec());
+
+    Ok((NetworkDegree::from_distance(distance), path))
+}
+
+/// Whether the viewer may learn that `a` and `b` are connected: it's their
+/// own connection, or either side's connection list is visible to them.
+pub fn edge_visible(access: &ViewerAccess, viewer_id: Uuid, a: Uuid, b: Uuid) -> bool {
+    a == viewer_id
+        || b == viewer_id
+        || access.can_see(a, Field::ConnectionList)
+        || access.can_see(b, Field::ConnectionList)
+}
+
+/// The viewer's first- and, if `max_distance` reaches it, second-degree
+/// contacts, walking around `excluded`.
+async fn circle(
+    pool: &PgPool,
+    viewer_id: Uuid,
+    max_distance: usize,
+    excluded: &HashSet<Uuid>,
+) -> Result<(HashSet<Uuid>, HashSet<Uuid>), sqlx::Error> {
+    let first: HashSet<Uuid> = neighbors(pool, &[viewer_id], excluded)
+        .await?
+        .remove(&viewer_id)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
+    let second: HashSet<Uuid> = if max_distance >= 2 {
+        let first_ids: Vec<Uuid> = first.iter().copied().collect();
+        neighbors(pool, &first_ids, excluded)
+            .await?
+            .into_values()
+            .flatten()
+            .filter(|id| *id != viewer_id && !first.contains(id))
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    Ok((first, second))
+}
+
+/// Everyone within two steps of the viewer, as first- and second-degree
+/// contacts, for checking audiences inside a query.
+pub async fn neighbourhood(pool: &PgPool, viewer_id: Uuid) -> Result<(HashSet<Uuid>, HashSet<Uuid>), sqlx::Error> {
+    let excluded = blocked_by_either(pool, viewer_id).await?;
+    circle(pool, viewer_id, 2, &excluded).await
+}
+
+/// The viewer's degree to each of `others`, walking outwards from the
+/// viewer two levels and meeting the others one level in from their side.
+/// Anyone further than `max_distance` is out of network, and the levels
+/// past it aren't looked up.
+pub async fn degrees(
+    pool: &PgPool,
+    viewer_id: Uuid,
+    others: &[Uuid],
+    max_distance: usize,
+) -> Result<HashMap<Uuid, NetworkDegree>, sqlx::Error> {
+    if others.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let excluded = blocked_by_either(pool, viewer_id).await?;
+    let (first, second) = circle(pool, viewer_id, max_distance, &excluded).await?;
+
+    let third: HashSet<Uuid> = if max_distance >= 3 {
+        let unresolved: Vec<Uuid> = others
+            .iter()
+            .filter(|id| **id != viewer_id && !first.contains(id) && !second.contains(id))
+            .copied()
+            .collect();
+        neighbors(pool, &unresolved, &excluded)
+            .await?
+            .into_iter()
+            .filter(|(_, tos)| tos.iter().any(|id| second.contains(id)))
+            .map(|(id, _)| id)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    Ok(others
+        .iter()
+        .map(|&id| {
+            let degree = if id == viewer_id {
+                NetworkDegree::Own
+            } else if first.contains(&id) {
+                NetworkDegree::First
+            } else if second.contains(&id) {
+                NetworkDegree::Second
+            } else if third.contains(&id) {
+                NetworkDegree::Third
+            } else {
+                NetworkDegree::OutOfNetwork
+            };
+            (id, degree)
+        })
+        .collect())
+}
+
+/// Degrees up to the second for many viewers at once, each to their own
+/// others, for batch jobs. However many viewers there are it takes two
+/// queries: one for blocks and one for the neighbours of every viewer and
+/// every other, since a second-degree contact shares a neighbour with a
+/// first-degree one.
+pub async fn close_degrees(
+    pool: &PgPool,
+    others_by_viewer: &HashMap<Uuid, Vec<Uuid>>,
+) -> Result<HashMap<Uuid, HashMap<Uuid, NetworkDegree>>, sqlx::Error> {
+    let viewers: Vec<Uuid> = others_by_viewer.keys().copied().collect();
+    let blocked = blocks::blocked_by_any(pool, &viewers).await?;
+
+    let ids: Vec<Uuid> = others_by_viewer
+        .iter()
+        .flat_map(|(viewer_id, others)| others.iter().copied().chain([*viewer_id]))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let adjacency = neighbors(pool, &ids, &HashSet::new()).await?;
+
+    let no_one = HashSet::new();
+    Ok(others_by_viewer
+        .iter()
+        .map(|(&viewer_id, others)| {
+            let excluded = blocked.get(&viewer_id).unwrap_or(&no_one);
+            let first: HashSet<Uuid> = adjacency
+                .get(&viewer_id)
+                .into_iter()
+                .flatten()
+                .filter(|id| !excluded.contains(id))
+                .copied()
+                .collect();
+
+            let degrees = others
+                .iter()
+                .map(|&id| {
+                    let degree = if id == viewer_id {
+                        NetworkDegree::Own
+                    } else if first.contains(&id) {
+                        NetworkDegree::First
+                    } else if !excluded.contains(&id)
+                        && adjacency.get(&id).into_iter().flatten().any(|via| first.contains(via))
+                    {
+                        NetworkDegree::Second
+                    } else {
+                        NetworkDegree::OutOfNetwork
+                    };
+                    (id, degree)
+                })
+                .collect();
+
+            (viewer_id, degrees)
+        })
+        .collect())
+}
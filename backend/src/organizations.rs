@@ -0,0 +1,97 @@
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+const LEGAL_SUFFIXES: &[&str] = &[
+    "inc", "incorporated", "llc", "ltd", "limited", "corp", "corporation", "co", "company",
+    "gmbh", "ag", "sa", "plc", "bv", "pty",
+];
+
+#[derive(Debug, Clone, Copy)]
+pub enum OrganizationKind {
+    Company,
+    School,
+}
+
+impl OrganizationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrganizationKind::Company => "company",
+            OrganizationKind::School => "school",
+        }
+    }
+}
+
+/// Matching key for an organization name: "Acme, Inc." and "ACME inc" both become "acme".
+pub fn normalize_name(name: &str) -> String {
+    let cleaned: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '&' { c } else { ' ' })
+        .collect();
+
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    while words.len() > 1 && words.last().is_some_and(|word| LEGAL_SUFFIXES.contains(word)) {
+        words.pop();
+    }
+
+    words.join(" ")
+}
+
+/// Links a free-text name to its canonical organization, creating it on first use.
+/// Names that normalize to nothing stay unlinked.
+pub async fn resolve(
+    conn: &mut PgConnection,
+    kind: OrganizationKind,
+    name: &str,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    let normalized = normalize_name(name);
+    if normalized.is_empty() {
+        return Ok(None);
+    }
+
+    let (id,): (Uuid,) = sqlx::query_as(
+        "INSERT INTO organizations (kind, name, normalized_name)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (kind, normalized_name) DO UPDATE SET kind = EXCLUDED.kind
+         RETURNING id"
+    )
+        .bind(kind.as_str())
+        .bind(name.trim())
+        .bind(&normalized)
+        .fetch_one(conn)
+        .await?;
+
+    Ok(Some(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_case_and_punctuation() {
+        assert_eq!(normalize_name("Acme, Inc."), "acme");
+        assert_eq!(normalize_name("ACME inc"), "acme");
+        assert_eq!(normalize_name("  Procter   &  Gamble "), "procter & gamble");
+    }
+
+    #[test]
+    fn strips_trailing_legal_suffixes() {
+        assert_eq!(normalize_name("Globex Corporation Ltd"), "globex");
+        assert_eq!(normalize_name("Initech GmbH"), "initech");
+        // Only at the end
+        assert_eq!(normalize_name("Co Op Bank"), "co op bank");
+    }
+
+    #[test]
+    fn keeps_a_name_that_is_only_a_suffix() {
+        assert_eq!(normalize_name("Company"), "company");
+        assert_eq!(normalize_name("Limited Inc"), "limited");
+    }
+
+    #[test]
+    fn empty_names_stay_empty() {
+        assert_eq!(normalize_name(""), "");
+        assert_eq!(normalize_name(" ,. "), "");
+    }
+}
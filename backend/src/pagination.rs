@@ -0,0 +1,221 @@
+//! Cursor pagination shared by list endpoints.
+//!
+//! A cursor holds the sort key of the last item on a page, so the next page
+//! picks up right after it even when rows are added or removed in between.
+//! Sort keys can carry data the client mustn't read, such as a hidden last
+//! name, so cursors are encrypted as well as tamper-proof. Each cursor is
+//! bound to the listing it came from and can't be replayed on another.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+pub const DEFAULT_LIMIT: i64 = 20;
+pub const MAX_LIMIT: i64 = 100;
+
+/// Totals are counted up to this many; beyond it they're reported as this.
+pub const TOTAL_ESTIMATE_CAP: i64 = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl PageQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+
+    /// Rows to fetch: one more than the page, to tell whether there's another.
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit() + 1
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidCursor;
+
+impl std::fmt::Display for InvalidCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Invalid cursor")
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Sealed<K> {
+    scope: String,
+    key: K,
+}
+
+/// Seals and opens cursors. Keys are derived from a server secret, so a
+/// restart with the same secret keeps outstanding cursors valid.
+#[derive(Clone)]
+pub struct Cursors {
+    key: [u8; 32],
+}
+
+impl Cursors {
+    pub fn from_secret(secret: &str) -> Self {
+        let digest = ring::digest::digest(&ring::digest::SHA256, format!("truelink-cursor:{}", secret).as_bytes());
+        Self { key: digest.as_ref().try_into().expect("SHA-256 digest is 32 bytes") }
+    }
+
+    fn aead_key(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, &self.key).expect("key is 32 bytes"))
+    }
+
+    /// A cursor for resuming `scope` after `key`.
+    pub fn encode<K: Serialize>(&self, scope: &str, key: &K) -> String {
+        let mut payload = serde_json::to_vec(&Sealed { scope: scope.to_string(), key }).expect("cursor key serializes");
+
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).expect("system randomness is available");
+
+        self.aead_key()
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(scope.as_bytes()), &mut payload)
+            .expect("cursor fits in one message");
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(payload);
+        URL_SAFE_NO_PAD.encode(sealed)
+    }
+
+    /// The key in `query`'s cursor, or `None` on the first page. Cursors
+    /// from another listing, or that were altered, are rejected.
+    pub fn decode<K: DeserializeOwned>(&self, scope: &str, query: &PageQuery) -> Result<Option<K>, InvalidCursor> {
+        let Some(cursor) = &query.cursor else {
+            return Ok(None);
+        };
+
+        let sealed = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| InvalidCursor)?;
+        if sealed.len() < NONCE_LEN {
+            return Err(InvalidCursor);
+        }
+
+        let (nonce, payload) = sealed.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| InvalidCursor)?;
+        let mut payload = payload.to_vec();
+
+        let opened = self
+            .aead_key()
+            .open_in_place(nonce, Aad::from(scope.as_bytes()), &mut payload)
+            .map_err(|_| InvalidCursor)?;
+
+        let sealed: Sealed<K> = serde_json::from_slice(opened).map_err(|_| InvalidCursor)?;
+        if sealed.scope != scope {
+            return Err(InvalidCursor);
+        }
+
+        Ok(Some(sealed.key))
+    }
+
+    /// Trims rows fetched with `PageQuery::fetch_limit` to one page, with a
+    /// cursor for the next when there is one.
+    pub fn page<T, K: Serialize>(
+        &self,
+        scope: &str,
+        query: &PageQuery,
+        mut rows: Vec<T>,
+        key: impl Fn(&T) -> K,
+    ) -> (Vec<T>, Option<String>) {
+        let limit = query.limit() as usize;
+        if rows.len() <= limit {
+            return (rows, None);
+        }
+
+        rows.truncate(limit);
+        let next_cursor = rows.last().map(|last| self.encode(scope, &key(last)));
+        (rows, next_cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(cursor: Option<String>, limit: Option<i64>) -> PageQuery {
+        PageQuery { cursor, limit }
+    }
+
+    #[test]
+    fn first_page_has_no_key() {
+        let cursors = Cursors::from_secret("secret");
+        assert_eq!(cursors.decode::<i64>("scope", &query(None, None)).unwrap(), None);
+    }
+
+    #[test]
+    fn round_trips_a_key() {
+        let cursors = Cursors::from_secret("secret");
+        let cursor = cursors.encode("scope", &(42i64, "Ada".to_string()));
+
+        let key: Option<(i64, String)> = cursors.decode("scope", &query(Some(cursor.clone()), None)).unwrap();
+        assert_eq!(key, Some((42, "Ada".to_string())));
+
+        // The same secret after a restart still opens it
+        let restarted = Cursors::from_secret("secret");
+        assert!(restarted.decode::<(i64, String)>("scope", &query(Some(cursor), None)).is_ok());
+    }
+
+    #[test]
+    fn keys_are_not_readable() {
+        let cursors = Cursors::from_secret("secret");
+        let cursor = cursors.encode("scope", &"Lovelace");
+        let sealed = URL_SAFE_NO_PAD.decode(cursor).unwrap();
+
+        assert!(!sealed.windows("Lovelace".len()).any(|window| window == b"Lovelace"));
+    }
+
+    #[test]
+    fn rejects_a_cursor_from_another_listing() {
+        let cursors = Cursors::from_secret("secret");
+        let cursor = cursors.encode("followers", &7i64);
+
+        assert!(cursors.decode::<i64>("following", &query(Some(cursor), None)).is_err());
+    }
+
+    #[test]
+    fn rejects_a_cursor_sealed_with_another_secret() {
+        let cursor = Cursors::from_secret("one").encode("scope", &7i64);
+
+        assert!(Cursors::from_secret("two").decode::<i64>("scope", &query(Some(cursor), None)).is_err());
+    }
+
+    #[test]
+    fn rejects_tampered_cursors() {
+        let cursors = Cursors::from_secret("secret");
+        let mut sealed = URL_SAFE_NO_PAD.decode(cursors.encode("scope", &7i64)).unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+
+        let tampered = URL_SAFE_NO_PAD.encode(&sealed);
+        assert!(cursors.decode::<i64>("scope", &query(Some(tampered), None)).is_err());
+        assert!(cursors.decode::<i64>("scope", &query(Some("not a cursor".to_string()), None)).is_err());
+        assert!(cursors.decode::<i64>("scope", &query(Some("AAAA".to_string()), None)).is_err());
+    }
+
+    #[test]
+    fn pages_trim_to_the_limit_with_a_cursor_for_the_rest() {
+        let cursors = Cursors::from_secret("secret");
+        let page = query(None, Some(2));
+
+        let (rows, next) = cursors.page("scope", &page, vec![1i64, 2, 3], |&row| row);
+        assert_eq!(rows, vec![1, 2]);
+
+        let next = query(next, Some(2));
+        assert_eq!(cursors.decode::<i64>("scope", &next).unwrap(), Some(2));
+
+        let (rows, next) = cursors.page("scope", &page, vec![3i64], |&row| row);
+        assert_eq!(rows, vec![3]);
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn limits_are_clamped() {
+        assert_eq!(query(None, None).limit(), DEFAULT_LIMIT);
+        assert_eq!(query(None, Some(0)).limit(), 1);
+        assert_eq!(query(None, Some(10_000)).limit(), MAX_LIMIT);
+        assert_eq!(query(None, Some(5)).fetch_limit(), 6);
+    }
+}
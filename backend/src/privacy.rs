@@ -0,0 +1,302 @@
+//! Every response that shows another user's data goes through here, so
+//! privacy settings are enforced in one place rather than per endpoint.
+
+use sqlx::{FromRow, PgPool};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::blocks;
+use crate::models::{Audience, PrivacySettings, UserCard};
+use crate::network::{self, NetworkDegree};
+
+/// No audience reaches past the second degree, so neither do the lookups.
+const AUDIENCE_DISTANCE: usize = 2;
+
+/// The parts of a user's data that have their own privacy setting.
+#[derive(Debug, Clone, Copy)]
+pub enum Field {
+    Email,
+    ProfileFields,
+    ConnectionList,
+    LastName,
+}
+
+impl Field {
+    /// The `privacy_settings` column holding this field's audience.
+    fn column(self) -> &'static str {
+        match self {
+            Field::Email => "email",
+            Field::ProfileFields => "profile_fields",
+            Field::ConnectionList => "connection_list",
+            Field::LastName => "last_name",
+        }
+    }
+
+    fn audience(self, settings: &PrivacySettings) -> Audience {
+        match self {
+            Field::Email => settings.email,
+            Field::ProfileFields => settings.profile_fields,
+            Field::ConnectionList => settings.connection_list,
+            Field::LastName => settings.last_name,
+        }
+    }
+}
+
+fn allows(audience: Audience, degree: NetworkDegree) -> bool {
+    match audience {
+        Audience::Public => true,
+        Audience::SecondDegree => {
+            matches!(degree, NetworkDegree::Own | NetworkDegree::First | NetworkDegree::Second)
+        }
+        Audience::Connections => matches!(degree, NetworkDegree::Own | NetworkDegree::First),
+        Audience::OnlyMe => degree == NetworkDegree::Own,
+    }
+}
+
+/// "Alice Smith" becomes "Alice S."; single names are left alone.
+pub fn shorten_name(full_name: &str) -> String {
+    let words: Vec<&str> = full_name.split_whitespace().collect();
+
+    match words.as_slice() {
+        [first, .., last] => match last.chars().next() {
+            Some(initial) => format!("{} {}.", first, initial),
+            None => first.to_string(),
+        },
+        _ => full_name.trim().to_string(),
+    }
+}
+
+/// What one viewer may see of a set of users.
+pub struct ViewerAccess {
+    settings: HashMap<Uuid, PrivacySettings>,
+    degrees: HashMap<Uuid, NetworkDegree>,
+    /// Owners who blocked the viewer or were blocked by them; they see nothing of each other.
+    blocked: HashSet<Uuid>,
+}
+
+impl ViewerAccess {
+    pub async fn load(pool: &PgPool, viewer_id: Option<Uuid>, owners: &[Uuid]) -> Result<Self, sqlx::Error> {
+        let settings: Vec<PrivacySettings> = sqlx::query_as("SELECT * FROM privacy_settings WHERE user_id = ANY($1)")
+            .bind(owners)
+            .fetch_all(pool)
+            .await?;
+
+        // Anonymous viewers are out of everyone's network
+        let (blocked, degrees) = match viewer_id {
+            Some(viewer_id) => (
+                blocks::blocked_among(pool, viewer_id, owners).await?,
+                network::degrees(pool, viewer_id, owners, AUDIENCE_DISTANCE).await?,
+            ),
+            None => (HashSet::new(), HashMap::new()),
+        };
+
+        Ok(Self {
+            settings: settings.into_iter().map(|s| (s.user_id, s)).collect(),
+            degrees,
+            blocked,
+        })
+    }
+
+    /// `load` for many viewers at once, each with their own owners, in a
+    /// fixed number of queries. For batch jobs that would otherwise load
+    /// once per user.
+    pub async fn load_many(
+        pool: &PgPool,
+        owners_by_viewer: &HashMap<Uuid, Vec<Uuid>>,
+    ) -> Result<HashMap<Uuid, Self>, sqlx::Error> {
+        let owners: Vec<Uuid> = owners_by_viewer.values().flatten().copied().collect::<HashSet<_>>().into_iter().collect();
+        let settings: HashMap<Uuid, PrivacySettings> =
+            sqlx::query_as::<_, PrivacySettings>("SELECT * FROM privacy_settings WHERE user_id = ANY($1)")
+                .bind(&owners)
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|s| (s.user_id, s))
+                .collect();
+
+        let viewers: Vec<Uuid> = owners_by_viewer.keys().copied().collect();
+        let mut blocked = blocks::blocked_by_any(pool, &viewers).await?;
+        let mut degrees = network::close_degrees(pool, owners_by_viewer).await?;
+
+        Ok(owners_by_viewer
+            .iter()
+            .map(|(viewer_id, owners)| {
+                let access = Self {
+                    settings: owners.iter().filter_map(|id| settings.get(id).map(|s| (*id, s.clone()))).collect(),
+                    degrees: degrees.remove(viewer_id).unwrap_or_default(),
+                    blocked: blocked.remove(viewer_id).unwrap_or_default(),
+                };
+                (*viewer_id, access)
+            })
+            .collect())
+    }
+
+    pub fn is_blocked(&self, owner_id: Uuid) -> bool {
+        self.blocked.contains(&owner_id)
+    }
+
+    pub fn degree(&self, owner_id: Uuid) -> NetworkDegree {
+        self.degrees.get(&owner_id).copied().unwrap_or(NetworkDegree::OutOfNetwork)
+    }
+
+    pub fn can_see(&self, owner_id: Uuid, field: Field) -> bool {
+        if self.is_blocked(owner_id) {
+            return false;
+        }
+
+        let defaults;
+        let settings = match self.settings.get(&owner_id) {
+            Some(settings) => settings,
+            None => {
+                defaults = PrivacySettings::defaults(owner_id);
+                &defaults
+            }
+        };
+
+        allows(field.audience(settings), self.degree(owner_id))
+    }
+
+    pub fn display_name(&self, owner_id: Uuid, full_name: &str) -> String {
+        if self.can_see(owner_id, Field::LastName) {
+            full_name.to_string()
+        } else {
+            shorten_name(full_name)
+        }
+    }
+}
+
+/// Where a viewer stands in the network, for checking audiences inside a
+/// query rather than on the rows it returned, so that a filter, the page it
+/// cuts and the total it counts all agree. Bind `viewer_id`, `first` and
+/// `second` to consecutive placeholders and pass the first of them to
+/// `visible_sql` and `display_name_sql`. Blocks aren't covered; queries
+/// leave blocked users out themselves.
+pub struct Neighbourhood {
+    pub viewer_id: Option<Uuid>,
+    pub first: Vec<Uuid>,
+    pub second: Vec<Uuid>,
+}
+
+impl Neighbourhood {
+    pub async fn load(pool: &PgPool, viewer_id: Option<Uuid>) -> Result<Self, sqlx::Error> {
+        // Anonymous viewers are out of everyone's network
+        let (first, second) = match viewer_id {
+            Some(viewer_id) => network::neighbourhood(pool, viewer_id).await?,
+            None => (HashSet::new(), HashSet::new()),
+        };
+
+        Ok(Self {
+            viewer_id,
+            first: first.into_iter().collect(),
+            second: second.into_iter().collect(),
+        })
+    }
+}
+
+/// SQL that's true when the viewer whose `Neighbourhood` is bound from
+/// `$param` may see `field` of the user with id `owner`; `can_see` in SQL.
+pub fn visible_sql(field: Field, owner: &str, param: usize) -> String {
+    let default = field.audience(&PrivacySettings::defaults(Uuid::nil()));
+
+    format!(
+        "COALESCE(CASE COALESCE((SELECT ps.{column} FROM privacy_settings ps WHERE ps.user_id = {owner}), '{default}')
+             WHEN 'public' THEN TRUE
+             WHEN 'second_degree' THEN {owner} = ${viewer} OR {owner} = ANY(${first}) OR {owner} = ANY(${second})
+             WHEN 'connections' THEN {owner} = ${viewer} OR {owner} = ANY(${first})
+             ELSE {owner} = ${viewer}
+         END, FALSE)",
+        column = field.column(),
+        default = default.as_str(),
+        viewer = param,
+        first = param + 1,
+        second = param + 2,
+    )
+}
+
+/// SQL for the name of the user with id `owner` as the viewer bound from
+/// `$param` sees it; `display_name` in SQL, shortening like `shorten_name`.
+pub fn display_name_sql(owner: &str, full_name: &str, param: usize) -> String {
+    format!(
+        r"CASE
+             WHEN {visible} THEN {full_name}
+             WHEN btrim({full_name}) ~ '\s'
+                 THEN substring(btrim({full_name}) from '^\S+') || ' ' || substring(btrim({full_name}) from '\s(\S)\S*$') || '.'
+             ELSE btrim({full_name})
+         END",
+        visible = visible_sql(Field::LastName, owner, param),
+    )
+}
+
+#[derive(FromRow)]
+struct UserRecord {
+    id: Uuid,
+    handle: String,
+    full_name: String,
+    email: String,
+    profile_picture_url: Option<String>,
+    headline: Option<String>,
+}
+
+/// Cards for `user_ids` as `viewer_id` may see them. Unknown ids are left
+/// out, and so are users blocked either way, so they vanish from every list.
+pub async fn user_cards(
+    pool: &PgPool,
+    viewer_id: Option<Uuid>,
+    user_ids: &[Uuid],
+) -> Result<HashMap<Uuid, UserCard>, sqlx::Error> {
+    let records: Vec<UserRecord> = sqlx::query_as(
+        "SELECT u.id, u.handle, u.full_name, u.email, u.profile_picture_url, p.headline
+         FROM users u
+         LEFT JOIN user_profiles p ON p.user_id = u.id
+         WHERE u.id = ANY($1)"
+    )
+        .bind(user_ids)
+        .fetch_all(pool)
+        .await?;
+
+    let access = ViewerAccess::load(pool, viewer_id, user_ids).await?;
+
+    Ok(records
+        .into_iter()
+        .filter(|record| !access.is_blocked(record.id))
+        .map(|record| {
+            let card = UserCard {
+                id: record.id,
+                full_name: access.display_name(record.id, &record.full_name),
+                handle: record.handle,
+                profile_picture_url: record.profile_picture_url,
+                email: access.can_see(record.id, Field::Email).then_some(record.email),
+                headline: record.headline.filter(|_| access.can_see(record.id, Field::ProfileFields)),
+            };
+            (card.id, card)
+        })
+        .collect())
+}
+
+/// Cards for users the viewer blocked, for managing the block list. They
+/// are shown as anyone logged out would see them.
+pub async fn blocked_user_cards(pool: &PgPool, user_ids: &[Uuid]) -> Result<HashMap<Uuid, UserCard>, sqlx::Error> {
+    user_cards(pool, None, user_ids).await
+}
+
+/// Pairs each item with the card of the user `user_id` picks out, keeping
+/// the input order and dropping items whose user no longer exists or is
+/// blocked.
+pub async fn attach_cards<T>(
+    pool: &PgPool,
+    viewer_id: Option<Uuid>,
+    items: Vec<T>,
+    user_id: impl Fn(&T) -> Uuid,
+) -> Result<Vec<(T, UserCard)>, sqlx::Error> {
+    let ids: Vec<Uuid> = items.iter().map(&user_id).collect();
+    let cards = user_cards(pool, viewer_id, &ids).await?;
+
+    Ok(items
+        .into_iter()
+        .filter_map(|item| {
+            let id = user_id(&item);
+            // The same user can appear more than once, so clone rather than remove
+            cards.get(&id).cloned().map(|card| (item, card))
+        })
+        .collect())
+}
@@ -0,0 +1,143 @@
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+use crate::models::{ProfileFieldChange, ProfileRevision};
+
+/// The editable profile fields, as stored and as snapshotted in each revision.
+#[derive(Debug, Clone, Default, PartialEq, Eq, FromRow)]
+pub struct ProfileFields {
+    pub headline: Option<String>,
+    pub summary: Option<String>,
+    pub location: Option<String>,
+    pub website: Option<String>,
+}
+
+impl ProfileFields {
+    fn entries(&self) -> [(&'static str, &Option<String>); 4] {
+        [
+            ("headline", &self.headline),
+            ("summary", &self.summary),
+            ("location", &self.location),
+            ("website", &self.website),
+        ]
+    }
+
+    /// Field-level changes from `self` to `after`.
+    pub fn diff(&self, after: &ProfileFields) -> Vec<ProfileFieldChange> {
+        self.entries()
+            .into_iter()
+            .zip(after.entries())
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((field, old), (_, new))| ProfileFieldChange {
+                field: field.to_string(),
+                old: old.clone(),
+                new: new.clone(),
+            })
+            .collect()
+    }
+}
+
+/// The user's current profile fields, locking the row until the transaction
+/// ends so concurrent edits get consecutive versions.
+pub async fn lock_current(conn: &mut PgConnection, user_id: Uuid) -> Result<ProfileFields, sqlx::Error> {
+    sqlx::query("INSERT INTO user_profiles (user_id) VALUES ($1) ON CONFLICT (user_id) DO NOTHING")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query_as("SELECT headline, summary, location, website FROM user_profiles WHERE user_id = $1 FOR UPDATE")
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+async fn insert_revision(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    actor_id: Option<Uuid>,
+    fields: &ProfileFields,
+    changes: &[ProfileFieldChange],
+    reverted_to: Option<i32>,
+) -> Result<ProfileRevision, sqlx::Error> {
+    sqlx::query_as(
+        "INSERT INTO profile_revisions
+            (user_id, version, actor_id, headline, summary, location, website, changes, reverted_to)
+         VALUES ($1, (SELECT COALESCE(MAX(version) + 1, 0) FROM profile_revisions WHERE user_id = $1),
+                 $2, $3, $4, $5, $6, $7, $8)
+         RETURNING *"
+    )
+        .bind(user_id)
+        .bind(actor_id)
+        .bind(&fields.headline)
+        .bind(&fields.summary)
+        .bind(&fields.location)
+        .bind(&fields.website)
+        .bind(sqlx::types::Json(changes))
+        .bind(reverted_to)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+/// Writes `after` to the user's profile and records it as a new revision by
+/// `actor_id`. Returns `None`, writing nothing, when nothing changed.
+///
+/// The first change to a profile also records version 0, the profile as it
+/// was before any tracked edit, so that state can be reverted to as well.
+pub async fn save(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    actor_id: Uuid,
+    after: &ProfileFields,
+    reverted_to: Option<i32>,
+) -> Result<Option<ProfileRevision>, sqlx::Error> {
+    let before = lock_current(conn, user_id).await?;
+
+    let changes = before.diff(after);
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let has_history: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM profile_revisions WHERE user_id = $1)")
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if !has_history {
+        insert_revision(conn, user_id, None, &before, &[], None).await?;
+    }
+
+    sqlx::query(
+        "UPDATE user_profiles SET headline = $2, summary = $3, location = $4, website = $5
+         WHERE user_id = $1"
+    )
+        .bind(user_id)
+        .bind(&after.headline)
+        .bind(&after.summary)
+        .bind(&after.location)
+        .bind(&after.website)
+        .execute(&mut *conn)
+        .await?;
+
+    insert_revision(conn, user_id, Some(actor_id), after, &changes, reverted_to)
+        .await
+        .map(Some)
+}
+
+pub async fn find(conn: &mut PgConnection, user_id: Uuid, version: i32) -> Result<Option<ProfileRevision>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM profile_revisions WHERE user_id = $1 AND version = $2")
+        .bind(user_id)
+        .bind(version)
+        .fetch_optional(&mut *conn)
+        .await
+}
+
+impl From<&ProfileRevision> for ProfileFields {
+    fn from(revision: &ProfileRevision) -> Self {
+        Self {
+            headline: revision.headline.clone(),
+            summary: revision.summary.clone(),
+            location: revision.location.clone(),
+            website: revision.website.clone(),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Records that `viewer_id` looked at `viewed_id`'s profile. Repeat views on
+/// the same day count once; the latest one keeps its timestamp. Views across
+/// a block aren't recorded.
+pub async fn record(pool: &PgPool, viewer_id: Uuid, viewed_id: Uuid) -> Result<(), sqlx::Error> {
+    if viewer_id == viewed_id {
+        return Ok(());
+    }
+
+    sqlx::query(
+        "INSERT INTO profile_views (viewer_id, viewed_id, anonymous, viewer_organization)
+         SELECT $1, $2,
+                COALESCE((SELECT browse_privately FROM privacy_settings WHERE user_id = $1), FALSE),
+                (SELECT company_name FROM positions
+                 WHERE user_id = $1 AND is_current
+                 ORDER BY sort_order
+                 LIMIT 1)
+         WHERE NOT EXISTS (
+             SELECT 1 FROM user_blocks
+             WHERE (blocker_id = $1 AND blocked_id = $2) OR (blocker_id = $2 AND blocked_id = $1)
+         )
+         ON CONFLICT (viewer_id, viewed_id, view_date) DO UPDATE SET
+             viewed_at = NOW(),
+             anonymous = EXCLUDED.anonymous,
+             viewer_organization = EXCLUDED.viewer_organization"
+    )
+        .bind(viewer_id)
+        .bind(viewed_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// "Someone at Acme", or just "Someone on TrueLink" when the viewer has no
+/// current position.
+pub fn anonymous_description(organization: Option<&str>) -> String {
+    match organization {
+        Some(organization) => format!("Someone at {}", organization),
+        None => "Someone on TrueLink".to_string(),
+    }
+}
+
+/// Folds raw views older than `retention_days` into daily counts and deletes
+/// them, in one statement so no view is lost or counted twice.
+pub async fn roll_up(pool: &PgPool, retention_days: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "WITH expired AS (
+             DELETE FROM profile_views
+             WHERE view_date < CURRENT_DATE - $1
+             RETURNING viewed_id, view_date, anonymous
+         )
+         INSERT INTO profile_view_daily (viewed_id, view_date, view_count, anonymous_count)
+         SELECT viewed_id, view_date, COUNT(*), COUNT(*) FILTER (WHERE anonymous)
+         FROM expired
+         GROUP BY viewed_id, view_date
+         ON CONFLICT (viewed_id, view_date) DO UPDATE SET
+             view_count = profile_view_daily.view_count + EXCLUDED.view_count,
+             anonymous_count = profile_view_daily.anonymous_count + EXCLUDED.anonymous_count"
+    )
+        .bind(retention_days)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub fn spawn_rollup_job(pool: PgPool, interval: Duration, retention_days: i32) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            match roll_up(&pool, retention_days).await {
+                Ok(0) => {}
+                Ok(days) => println!("🗂️  Rolled up profile views into {} daily counts", days),
+                Err(e) => eprintln!("Profile view rollup error: {}", e),
+            }
+        }
+    });
+}
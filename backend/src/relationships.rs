@@ -0,0 +1,414 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::blocks;
+use crate::models::{Connection, ConnectionStatus};
+use crate::request_policy::RequestPolicy;
+
+/// Whether the two users share an accepted connection, in either direction.
+pub async fn are_connected(pool: &PgPool, a: Uuid, b: Uuid) -> Result<bool, sqlx::Error> {
+    let (connected,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS(
+            SELECT 1 FROM connections
+            WHERE status = 'accepted'
+              AND ((sender_id = $1 AND receiver_id = $2) OR (sender_id = $2 AND receiver_id = $1))
+        )"
+    )
+        .bind(a)
+        .bind(b)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(connected)
+}
+
+/// What went away with a connection.
+#[derive(Debug, Serialize)]
+pub struct DisconnectSummary {
+    pub endorsements_removed: u64,
+    pub vouches_removed: u64,
+    /// Open requests and drafts awaiting approval
+    pub recommendations_removed: u64,
+    /// Published recommendations, kept for their authors but taken off the profile
+    pub recommendations_hidden: u64,
+}
+
+/// Removes the accepted connection between `a` and `b` along with everything
+/// that required it. Returns `None` when they weren't connected.
+pub async fn disconnect(conn: &mut PgConnection, a: Uuid, b: Uuid) -> Result<Option<DisconnectSummary>, sqlx::Error> {
+    let removed = sqlx::query(
+        "DELETE FROM connections
+         WHERE status = 'accepted'
+           AND ((sender_id = $1 AND receiver_id = $2) OR (sender_id = $2 AND receiver_id = $1))"
+    )
+        .bind(a)
+        .bind(b)
+        .execute(&mut *conn)
+        .await?;
+
+    if removed.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    let endorsements_removed = sqlx::query(
+        "DELETE FROM endorsements
+         WHERE (endorser_id = $1 AND user_id = $2) OR (endorser_id = $2 AND user_id = $1)"
+    )
+        .bind(a)
+        .bind(b)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    let vouches_removed = sqlx::query(
+        "DELETE FROM vouches
+         WHERE (voucher_id = $1 AND vouchee_id = $2) OR (voucher_id = $2 AND vouchee_id = $1)"
+    )
+        .bind(a)
+        .bind(b)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    let recommendations_removed = sqlx::query(
+        "DELETE FROM recommendations
+         WHERE status IN ('requested', 'pending_approval')
+           AND ((author_id = $1 AND recipient_id = $2) OR (author_id = $2 AND recipient_id = $1))"
+    )
+        .bind(a)
+        .bind(b)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    let recommendations_hidden = sqlx::query(
+        "UPDATE recommendations SET status = 'hidden', updated_at = NOW()
+         WHERE status = 'visible'
+           AND ((author_id = $1 AND recipient_id = $2) OR (author_id = $2 AND recipient_id = $1))"
+    )
+        .bind(a)
+        .bind(b)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    Ok(Some(DisconnectSummary {
+        endorsements_removed,
+        vouches_removed,
+        recommendations_removed,
+        recommendations_hidden,
+    }))
+}
+
+/// Serializes changes to the relationship between `a` and `b` until the
+/// transaction ends, whichever of them acts.
+pub async fn lock_pair(conn: &mut PgConnection, a: Uuid, b: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended(LEAST($1, $2)::text || GREATEST($1, $2)::text, 0))")
+        .bind(a)
+        .bind(b)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// The one row for the pair, whichever way round it was sent.
+pub async fn find_pair(conn: &mut PgConnection, a: Uuid, b: Uuid) -> Result<Option<Connection>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT * FROM connections
+         WHERE LEAST(sender_id, receiver_id) = LEAST($1, $2)
+           AND GREATEST(sender_id, receiver_id) = GREATEST($1, $2)"
+    )
+        .bind(a)
+        .bind(b)
+        .fetch_optional(&mut *conn)
+        .await
+}
+
+#[derive(Debug)]
+pub enum RequestOutcome {
+    /// Delivered, or as far as the sender can tell delivered.
+    Sent(Uuid),
+    /// The other user had already asked, so the request accepted theirs.
+    AutoAccepted(Uuid),
+    AlreadyPending(Uuid),
+    AlreadyConnected(Uuid),
+    /// One of them blocked the other.
+    Blocked,
+    /// Too many of the sender's requests were rejected lately.
+    CoolingDown(DateTime<Utc>),
+}
+
+#[derive(FromRow)]
+struct RejectionHistory {
+    recent: i64,
+    last: Option<DateTime<Utc>>,
+    last_for_pair: Option<DateTime<Utc>>,
+}
+
+/// Asks to connect `sender_id` with `receiver_id`, keeping one row per pair.
+///
+/// Senders never learn they were rejected: a rejected request keeps
+/// looking pending to them, and asking again within the pair's cooldown
+/// quietly files the new request as rejected too.
+pub async fn request_connection(
+    conn: &mut PgConnection,
+    policy: &RequestPolicy,
+    sender_id: Uuid,
+    receiver_id: Uuid,
+    note: Option<&str>,
+) -> Result<RequestOutcome, StatusError> {
+    lock_pair(conn, sender_id, receiver_id).await?;
+
+    if blocks::is_blocked(conn, sender_id, receiver_id).await? {
+        return Ok(RequestOutcome::Blocked);
+    }
+
+    let existing = find_pair(conn, sender_id, receiver_id).await?;
+
+    // Answering someone who is waiting on you is never held back
+    if let Some(existing) = &existing {
+        match existing.status {
+            ConnectionStatus::Accepted => return Ok(RequestOutcome::AlreadyConnected(existing.id)),
+            // Asking someone you ignored takes them up on it after all
+            ConnectionStatus::Pending | ConnectionStatus::Ignored if existing.receiver_id == sender_id => {
+                set_status(conn, existing, ConnectionStatus::Accepted).await?;
+
+                // The second note answers the first
+                sqlx::query("UPDATE connections SET reply = $2 WHERE id = $1")
+                    .bind(existing.id)
+                    .bind(note)
+                    .execute(&mut *conn)
+                    .await?;
+                return Ok(RequestOutcome::AutoAccepted(existing.id));
+            }
+            // To its sender an ignored request lapses like any other, so
+            // past that point asking again sends it afresh
+            ConnectionStatus::Ignored if existing.lapsed(policy.request_expiry) => {}
+            ConnectionStatus::Pending | ConnectionStatus::Ignored => return Ok(RequestOutcome::AlreadyPending(existing.id)),
+            ConnectionStatus::Rejected | ConnectionStatus::Expired => {}
+        }
+    }
+
+    let history: RejectionHistory = sqlx::query_as(
+        "SELECT COUNT(*) FILTER (WHERE rejected_at > NOW() - $3) AS recent,
+                MAX(rejected_at) AS last,
+                MAX(rejected_at) FILTER (WHERE receiver_id = $2) AS last_for_pair
+         FROM connection_rejections
+         WHERE sender_id = $1"
+    )
+        .bind(sender_id)
+        .bind(receiver_id)
+        .bind(policy.rejection_window)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if let Some(until) = policy.sender_blocked_until(history.recent, history.last) {
+        return Ok(RequestOutcome::CoolingDown(until));
+    }
+
+    let pair_cooling_down = history
+        .last_for_pair
+        .is_some_and(|rejected_at| rejected_at + policy.rerequest_cooldown > Utc::now());
+
+    match existing {
+        // A rejection still cooling down looks like the pending request the
+        // sender thinks it is, or once that has seemingly lapsed, is re-filed unseen
+        Some(existing)
+            if pair_cooling_down && existing.sender_id == sender_id && existing.status == ConnectionStatus::Rejected =>
+        {
+            if !existing.lapsed(policy.request_expiry) {
+                return Ok(RequestOutcome::AlreadyPending(existing.id));
+            }
+
+            sqlx::query("UPDATE connections SET note = $2, reply = NULL, requested_at = NOW() WHERE id = $1")
+                .bind(existing.id)
+                .bind(note)
+                .execute(&mut *conn)
+                .await?;
+            Ok(RequestOutcome::Sent(existing.id))
+        }
+        Some(existing) => {
+            // Re-sending an expired request during a cooldown is shelved unseen, as below
+            let status = if pair_cooling_down && existing.sender_id == sender_id {
+                ConnectionStatus::Rejected
+            } else {
+                ConnectionStatus::Pending
+            };
+
+            set_status(conn, &existing, status).await?;
+
+            // Asking again turns the old row around to point from the new sender
+            sqlx::query(
+                "UPDATE connections
+                 SET sender_id = $2, receiver_id = $3, note = $4, reply = NULL, requested_at = NOW()
+                 WHERE id = $1"
+            )
+                .bind(existing.id)
+                .bind(sender_id)
+                .bind(receiver_id)
+                .bind(note)
+                .execute(&mut *conn)
+                .await?;
+            Ok(RequestOutcome::Sent(existing.id))
+        }
+        None => {
+            // A withdrawn rejection doesn't reset the cooldown; the new request is shelved unseen
+            let status = if pair_cooling_down { ConnectionStatus::Rejected } else { ConnectionStatus::Pending };
+            let id: Uuid = sqlx::query_scalar(
+                "INSERT INTO connections (sender_id, receiver_id, status, note) VALUES ($1, $2, $3, $4) RETURNING id"
+            )
+                .bind(sender_id)
+                .bind(receiver_id)
+                .bind(status)
+                .bind(note)
+                .fetch_one(&mut *conn)
+                .await?;
+            Ok(RequestOutcome::Sent(id))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StatusError {
+    NotFound,
+    /// The move isn't allowed from the connection's current status.
+    IllegalTransition(ConnectionStatus),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for StatusError {
+    fn from(e: sqlx::Error) -> Self {
+        StatusError::Database(e)
+    }
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StatusError::NotFound => write!(f, "Connection not found"),
+            StatusError::IllegalTransition(current) => write!(f, "Connection is already {}", current),
+            StatusError::Database(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Moves `existing` to `next`. Every status change goes through here, so
+/// none can get around `ConnectionStatus::can_transition_to`. The row must
+/// still have the status it was read with. Accepting stamps `accepted_at`.
+async fn set_status(conn: &mut PgConnection, existing: &Connection, next: ConnectionStatus) -> Result<(), StatusError> {
+    if !existing.status.can_transition_to(next) {
+        return Err(StatusError::IllegalTransition(existing.status));
+    }
+
+    let result = sqlx::query(
+        "UPDATE connections
+         SET status = $3, accepted_at = CASE WHEN $4 THEN NOW() ELSE accepted_at END
+         WHERE id = $1 AND status = $2"
+    )
+        .bind(existing.id)
+        .bind(existing.status)
+        .bind(next)
+        .bind(next == ConnectionStatus::Accepted)
+        .execute(&mut *conn)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(StatusError::NotFound);
+    }
+
+    Ok(())
+}
+
+/// Moves a request addressed to `receiver_id` to `next`, if the state
+/// machine allows it, along with the receiver's reply to the sender's note.
+pub async fn answer_request(
+    conn: &mut PgConnection,
+    connection_id: Uuid,
+    receiver_id: Uuid,
+    next: ConnectionStatus,
+    reply: Option<&str>,
+) -> Result<(), StatusError> {
+    let existing: Connection = sqlx::query_as("SELECT * FROM connections WHERE id = $1 AND receiver_id = $2 FOR UPDATE")
+        .bind(connection_id)
+        .bind(receiver_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(StatusError::NotFound)?;
+
+    // Re-sending after a rejection is the sender's move, and expiry the
+    // clock's, not an answer
+    let answer = matches!(next, ConnectionStatus::Accepted | ConnectionStatus::Rejected | ConnectionStatus::Ignored);
+    if !answer {
+        return Err(StatusError::IllegalTransition(existing.status));
+    }
+
+    set_status(conn, &existing, next).await?;
+
+    if reply.is_some() {
+        sqlx::query("UPDATE connections SET reply = $2 WHERE id = $1")
+            .bind(connection_id)
+            .bind(reply)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    if next == ConnectionStatus::Rejected {
+        sqlx::query("INSERT INTO connection_rejections (sender_id, receiver_id) VALUES ($1, $2)")
+            .bind(existing.sender_id)
+            .bind(receiver_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Expires requests sent more than `after` ago that are still pending.
+/// Rejected and ignored ones keep their status, which re-request cooldowns
+/// depend on; `Connection::status_for_as_of` makes them look expired to
+/// their sender all the same.
+pub async fn expire_requests(pool: &PgPool, after: chrono::Duration) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Requests someone is answering right now are left for the next run
+    let stale: Vec<Connection> = sqlx::query_as(
+        "SELECT * FROM connections
+         WHERE status = 'pending' AND requested_at < NOW() - $1
+         FOR UPDATE SKIP LOCKED"
+    )
+        .bind(after)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    let mut expired = 0;
+    for connection in &stale {
+        match set_status(&mut tx, connection, ConnectionStatus::Expired).await {
+            Ok(()) => expired += 1,
+            Err(StatusError::Database(e)) => return Err(e),
+            Err(_) => {}
+        }
+    }
+
+    tx.commit().await?;
+    Ok(expired)
+}
+
+/// Background job: expires stale requests every `interval`.
+pub fn spawn_expiry_job(pool: PgPool, interval: std::time::Duration, after: chrono::Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            match expire_requests(&pool, after).await {
+                Ok(0) => {}
+                Ok(count) => println!("⌛ Expired {} connection requests", count),
+                Err(e) => eprintln!("Connection request expiry error: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Duration, Utc};
+use std::env;
+
+/// When a rejected sender may ask again, and how long anyone waits for an answer.
+///
+/// Each pair has a fixed cooldown after a rejection. On top of that, a
+/// sender with too many recent rejections waits before sending to anyone,
+/// and the wait doubles with every rejection past the threshold.
+#[derive(Debug, Clone)]
+pub struct RequestPolicy {
+    /// How long after a rejection the same sender may ask the same receiver again.
+    pub rerequest_cooldown: Duration,
+    /// How far back a sender's rejections count towards their rate.
+    pub rejection_window: Duration,
+    /// Rejections within the window before a sender-wide cooldown starts.
+    pub rejection_threshold: i64,
+    /// The first sender-wide cooldown, doubled for each further rejection.
+    pub base_penalty: Duration,
+    pub max_penalty: Duration,
+    /// How long a request may go unanswered before it expires.
+    pub request_expiry: Duration,
+}
+
+/// The longest any configured period may be. A century is as good as
+/// forever here, and keeps every date computed from it in range.
+const MAX_DAYS: i64 = 36_500;
+
+fn env_number(name: &str, default: i64) -> i64 {
+    env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+fn env_days(name: &str, default: i64) -> Duration {
+    Duration::days(env_number(name, default).clamp(0, MAX_DAYS))
+}
+
+fn env_hours(name: &str, default: i64) -> Duration {
+    Duration::hours(env_number(name, default).clamp(0, MAX_DAYS * 24))
+}
+
+impl RequestPolicy {
+    pub fn from_env() -> Self {
+        Self {
+            rerequest_cooldown: env_days("CONNECTION_REREQUEST_COOLDOWN_DAYS", 30),
+            rejection_window: env_days("CONNECTION_REJECTION_WINDOW_DAYS", 30),
+            rejection_threshold: env_number("CONNECTION_REJECTION_THRESHOLD", 5),
+            base_penalty: env_hours("CONNECTION_REJECTION_PENALTY_HOURS", 1),
+            max_penalty: env_days("CONNECTION_REJECTION_MAX_PENALTY_DAYS", 30),
+            request_expiry: env_days("CONNECTION_REQUEST_EXPIRY_DAYS", 90),
+        }
+    }
+
+    /// The sender-wide cooldown for `recent_rejections` within the window, if any.
+    pub fn sender_penalty(&self, recent_rejections: i64) -> Option<Duration> {
+        let excess = recent_rejections - self.rejection_threshold;
+        if excess < 0 {
+            return None;
+        }
+
+        // Past 2^20 the cap has long since applied
+        let factor = 1_i32 << excess.min(20);
+        let penalty = self.base_penalty.checked_mul(factor).unwrap_or(self.max_penalty);
+        Some(penalty.min(self.max_penalty))
+    }
+
+    /// Until when a sender with these recent rejections is held back, if they are.
+    pub fn sender_blocked_until(&self, recent_rejections: i64, last_rejection: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        let until = last_rejection?
+            .checked_add_signed(self.sender_penalty(recent_rejections)?)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        (until > Utc::now()).then_some(until)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RequestPolicy {
+        RequestPolicy {
+            rerequest_cooldown: Duration::days(30),
+            rejection_window: Duration::days(30),
+            rejection_threshold: 5,
+            base_penalty: Duration::hours(1),
+            max_penalty: Duration::days(30),
+            request_expiry: Duration::days(90),
+        }
+    }
+
+    #[test]
+    fn no_penalty_below_the_threshold() {
+        assert_eq!(policy().sender_penalty(0), None);
+        assert_eq!(policy().sender_penalty(4), None);
+    }
+
+    #[test]
+    fn penalty_doubles_past_the_threshold() {
+        let policy = policy();
+        assert_eq!(policy.sender_penalty(5), Some(Duration::hours(1)));
+        assert_eq!(policy.sender_penalty(6), Some(Duration::hours(2)));
+        assert_eq!(policy.sender_penalty(8), Some(Duration::hours(8)));
+    }
+
+    #[test]
+    fn penalty_is_capped() {
+        let policy = policy();
+        assert_eq!(policy.sender_penalty(15), Some(Duration::days(30)));
+        assert_eq!(policy.sender_penalty(i64::MAX), Some(Duration::days(30)));
+    }
+
+    #[test]
+    fn huge_base_penalty_saturates_at_the_cap() {
+        let policy = RequestPolicy { base_penalty: Duration::days(MAX_DAYS), max_penalty: Duration::days(MAX_DAYS), ..policy() };
+        assert_eq!(policy.sender_penalty(100), Some(Duration::days(MAX_DAYS)));
+    }
+
+    #[test]
+    fn blocked_only_until_the_penalty_runs_out() {
+        let policy = policy();
+        let now = Utc::now();
+        assert!(policy.sender_blocked_until(5, Some(now)).is_some());
+        assert_eq!(policy.sender_blocked_until(5, Some(now - Duration::hours(2))), None);
+        assert_eq!(policy.sender_blocked_until(5, None), None);
+    }
+}
@@ -0,0 +1,360 @@
+//! Conversion between profiles and JSON Resume documents (https://jsonresume.org/schema).
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::{Education, EducationRequest, Position, PositionRequest, UpdateProfileRequest};
+
+pub const SCHEMA_URL: &str = "https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json";
+pub const SCHEMA_VERSION: &str = "v1.0.0";
+
+// Every field is optional: exports omit what the profile doesn't have, and
+// imports accept whatever subset another tool produced.
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JsonResume {
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub basics: Basics,
+    #[serde(default)]
+    pub work: Vec<Work>,
+    #[serde(default)]
+    pub education: Vec<ResumeEducation>,
+    #[serde(default)]
+    pub skills: Vec<ResumeSkill>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Basics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<SocialProfile>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+}
+
+impl Location {
+    /// Our single free-text location, built from whichever parts are present.
+    fn to_text(&self) -> Option<String> {
+        let parts: Vec<&str> = [&self.address, &self.city, &self.region, &self.country_code]
+            .into_iter()
+            .filter_map(|part| part.as_deref().map(str::trim).filter(|part| !part.is_empty()))
+            .collect();
+
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SocialProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Work {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeEducation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub institution: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub study_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResumeSkill {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+/// ISO 8601 dates as JSON Resume writes them: "2020-03-15", "2020-03" or "2020".
+fn parse_year_month(date: &str) -> Option<(i32, i32)> {
+    let mut parts = date.trim().split('-');
+    let year = parts.next()?.parse().ok()?;
+    let month = match parts.next() {
+        Some(month) => month.parse().ok()?,
+        None => 1,
+    };
+    Some((year, month))
+}
+
+fn parse_year(date: &str) -> Option<i32> {
+    parse_year_month(date).map(|(year, _)| year)
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+pub fn work_from_position(position: &Position) -> Work {
+    Work {
+        name: Some(position.company_name.clone()),
+        position: Some(position.title.clone()),
+        start_date: Some(format!("{:04}-{:02}", position.start_year, position.start_month)),
+        end_date: position
+            .end_year
+            .zip(position.end_month)
+            .map(|(year, month)| format!("{:04}-{:02}", year, month)),
+        summary: position.description.clone(),
+    }
+}
+
+pub fn education_from_entry(education: &Education) -> ResumeEducation {
+    ResumeEducation {
+        institution: Some(education.school_name.clone()),
+        area: education.field_of_study.clone(),
+        study_type: education.degree.clone(),
+        start_date: education.start_year.map(|year| year.to_string()),
+        end_date: education.end_year.map(|year| year.to_string()),
+    }
+}
+
+/// Converts one `work` entry, with a reason when it can't be imported.
+pub fn position_from_work(work: &Work) -> Result<PositionRequest, String> {
+    let (start_year, start_month) = work
+        .start_date
+        .as_deref()
+        .and_then(parse_year_month)
+        .ok_or("startDate is missing or not a date")?;
+
+    let end = match work.end_date.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(date) => Some(parse_year_month(date).ok_or("endDate is not a date")?),
+        None => None,
+    };
+
+    let position = PositionRequest {
+        company_name: non_empty(&work.name).ok_or("name is missing")?,
+        title: non_empty(&work.position).ok_or("position is missing")?,
+        start_year,
+        start_month,
+        end_year: end.map(|(year, _)| year),
+        end_month: end.map(|(_, month)| month),
+        is_current: end.is_none(),
+        description: non_empty(&work.summary),
+    };
+
+    position.validate().map_err(|e| e.to_string())?;
+    Ok(position)
+}
+
+pub fn education_from_resume(education: &ResumeEducation) -> Result<EducationRequest, String> {
+    let entry = EducationRequest {
+        school_name: non_empty(&education.institution).ok_or("institution is missing")?,
+        degree: non_empty(&education.study_type),
+        field_of_study: non_empty(&education.area),
+        start_year: education.start_date.as_deref().and_then(parse_year),
+        end_year: education.end_date.as_deref().and_then(parse_year),
+    };
+
+    entry.validate().map_err(|e| e.to_string())?;
+    Ok(entry)
+}
+
+/// Profile fields from `basics`, each checked on its own so one bad value
+/// doesn't block the rest. Returns (field, value) pairs and per-field errors.
+pub fn profile_fields_from_basics(basics: &Basics) -> (Vec<(&'static str, String)>, Vec<String>) {
+    let candidates = [
+        ("headline", non_empty(&basics.label)),
+        ("summary", non_empty(&basics.summary)),
+        ("location", basics.location.as_ref().and_then(Location::to_text)),
+        ("website", non_empty(&basics.url)),
+    ];
+
+    let mut fields = Vec::new();
+    let mut errors = Vec::new();
+
+    for (field, value) in candidates {
+        let Some(value) = value else { continue };
+
+        let mut request = UpdateProfileRequest { headline: None, summary: None, location: None, website: None };
+        match field {
+            "headline" => request.headline = Some(value.clone()),
+            "summary" => request.summary = Some(value.clone()),
+            "location" => request.location = Some(value.clone()),
+            _ => request.website = Some(value.clone()),
+        }
+
+        match request.validate() {
+            Ok(()) => fields.push((field, value)),
+            Err(e) => errors.push(format!("basics.{}: {}", field, e)),
+        }
+    }
+
+    (fields, errors)
+}
+
+/// Skill names from `skills`. JSON Resume often uses `name` for a group
+/// ("Web Development") with the actual skills as keywords, so keywords win
+/// when present.
+pub fn skill_names(skills: &[ResumeSkill]) -> Vec<String> {
+    skills
+        .iter()
+        .flat_map(|skill| {
+            if skill.keywords.is_empty() {
+                skill.name.iter().collect::<Vec<_>>()
+            } else {
+                skill.keywords.iter().collect()
+            }
+        })
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && name.len() <= 100)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work(name: &str, position: &str, start: Option<&str>, end: Option<&str>) -> Work {
+        Work {
+            name: Some(name.to_string()),
+            position: Some(position.to_string()),
+            start_date: start.map(str::to_string),
+            end_date: end.map(str::to_string),
+            summary: None,
+        }
+    }
+
+    #[test]
+    fn accepts_profiles_without_a_username() {
+        let resume: JsonResume = serde_json::from_str(
+            r#"{"basics":{"profiles":[{"network":"Twitter","url":"https://twitter.com/alice"},{"username":"alice"}]}}"#,
+        )
+        .unwrap();
+
+        let profiles = &resume.basics.profiles;
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].network.as_deref(), Some("Twitter"));
+        assert_eq!(profiles[0].username, None);
+        assert_eq!(profiles[1].url, None);
+    }
+
+    #[test]
+    fn parses_year_month_at_every_precision() {
+        assert_eq!(parse_year_month("2020-03-15"), Some((2020, 3)));
+        assert_eq!(parse_year_month("2020-03"), Some((2020, 3)));
+        assert_eq!(parse_year_month(" 2020 "), Some((2020, 1)));
+        assert_eq!(parse_year_month(""), None);
+        assert_eq!(parse_year_month("March 2020"), None);
+        assert_eq!(parse_year_month("2020-Mar"), None);
+    }
+
+    #[test]
+    fn converts_work_into_positions() {
+        let ended = position_from_work(&work(" Acme ", "Engineer", Some("2018-04-01"), Some("2020-06"))).unwrap();
+        assert_eq!(ended.company_name, "Acme");
+        assert_eq!((ended.start_year, ended.start_month), (2018, 4));
+        assert_eq!((ended.end_year, ended.end_month), (Some(2020), Some(6)));
+        assert!(!ended.is_current);
+
+        let current = position_from_work(&work("Acme", "Engineer", Some("2019"), Some(" "))).unwrap();
+        assert_eq!((current.start_year, current.start_month), (2019, 1));
+        assert_eq!((current.end_year, current.end_month), (None, None));
+        assert!(current.is_current);
+    }
+
+    #[test]
+    fn explains_why_work_cannot_be_imported() {
+        let reason = |work: Work| position_from_work(&work).unwrap_err();
+
+        assert_eq!(reason(work("Acme", "Engineer", None, None)), "startDate is missing or not a date");
+        assert_eq!(reason(work("Acme", "Engineer", Some("2019"), Some("soon"))), "endDate is not a date");
+        assert_eq!(reason(work(" ", "Engineer", Some("2019"), None)), "name is missing");
+        assert_eq!(reason(work("Acme", "", Some("2019"), None)), "position is missing");
+        assert!(reason(work("Acme", "Engineer", Some("2019-13"), None)).contains("1-12"));
+        assert!(reason(work("Acme", "Engineer", Some("2020"), Some("2019"))).contains("before start date"));
+    }
+
+    #[test]
+    fn prefers_keywords_over_group_names() {
+        let skills = vec![
+            ResumeSkill { name: Some("Web Development".to_string()), keywords: vec!["HTML".to_string(), " CSS ".to_string()] },
+            ResumeSkill { name: Some(" Rust ".to_string()), keywords: Vec::new() },
+            ResumeSkill { name: None, keywords: Vec::new() },
+            ResumeSkill { name: Some("x".repeat(101)), keywords: Vec::new() },
+            ResumeSkill { name: Some("  ".to_string()), keywords: Vec::new() },
+        ];
+
+        assert_eq!(skill_names(&skills), ["HTML", "CSS", "Rust"]);
+    }
+
+    #[test]
+    fn joins_the_location_parts_that_are_present() {
+        let location = Location {
+            address: Some(" ".to_string()),
+            postal_code: Some("10115".to_string()),
+            city: Some("Berlin".to_string()),
+            region: None,
+            country_code: Some("DE".to_string()),
+        };
+
+        assert_eq!(location.to_text().as_deref(), Some("Berlin, DE"));
+        assert_eq!(Location::default().to_text(), None);
+    }
+}
@@ -4,14 +4,18 @@ use axum::{
     response::IntoResponse,
     Json,
 };
+use sqlx::Connection;
 use validator::Validate;
 
 use crate::{
-    models::{CreateUserRequest, User, AuthResponse},
-    
+    credentials, handles,
+    models::{CreateUserRequest, User, AuthResponse, VerificationTier},
 };
 use crate::routes::AppState;
 
+/// Tries at a free handle before giving up on a registration.
+const MAX_HANDLE_ATTEMPTS: u32 = 5;
+
 pub async fn register(
     State(state): State<AppState>,
     Json(payload): Json<CreateUserRequest>,
@@ -38,23 +42,59 @@ pub async fn register(
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    let user: User = sqlx::query_as(
-        "INSERT INTO users (email, full_name, verification_tier)
-         VALUES ($1, $2, $3)
-         RETURNING *"
-    )
-        .bind(&payload.email)
-        .bind(&payload.full_name)
-        .bind("standard")
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    // Someone registering at the same moment can take the suggested handle
+    // first, so a clash on it means picking another and trying again
+    let mut attempts = 0;
+    let user: User = loop {
+        let handle = handles::suggest(&mut tx, &payload.full_name)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+        let mut savepoint = tx.begin()
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+        let inserted = sqlx::query_as(
+            "INSERT INTO users (email, full_name, handle, verification_tier)
+             VALUES ($1, $2, $3, $4)
+             RETURNING *"
+        )
+            .bind(&payload.email)
+            .bind(&payload.full_name)
+            .bind(&handle)
+            .bind(VerificationTier::Standard.as_str())
+            .fetch_one(&mut *savepoint)
+            .await;
+
+        match inserted {
+            Ok(user) => {
+                savepoint.commit()
+                    .await
+                    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                break user;
+            }
+            Err(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+                savepoint.rollback()
+                    .await
+                    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+                if db.constraint() != Some("idx_users_handle_lower") {
+                    return Err((StatusCode::CONFLICT, "User already exists".to_string()));
+                }
+                attempts += 1;
+                if attempts == MAX_HANDLE_ATTEMPTS {
+                    return Err((StatusCode::CONFLICT, "Could not reserve a handle, please try again".to_string()));
+                }
+            }
+            Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        }
+    };
 
     sqlx::query(
         "INSERT INTO auth_methods (user_id, provider, provider_user_id, password_hash)
          VALUES ($1, $2, $3, $4)"
     )
-        .bind(&user.id)
+        .bind(user.id)
         .bind("email")
         .bind(&user.email)
         .bind(&password_hash)
@@ -62,10 +102,26 @@ pub async fn register(
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    sqlx::query("INSERT INTO user_profiles (user_id) VALUES ($1)")
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sqlx::query("INSERT INTO privacy_settings (user_id) VALUES ($1)")
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
     tx.commit()
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    if let Err(e) = credentials::grant_tier(&state.pool, &state.issuer, user.id, VerificationTier::Standard).await {
+        eprintln!("Failed to issue verification credential for {}: {}", user.id, e);
+    }
+
     let token = state.auth_service
         .generate_token(user.id, &user.email)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -92,7 +148,7 @@ pub async fn login(
     let auth_method: Option<(String,)> = sqlx::query_as(
         "SELECT password_hash FROM auth_methods WHERE user_id = $1 AND provider = 'email'"
     )
-        .bind(&user.id)
+        .bind(user.id)
         .fetch_optional(&state.pool)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
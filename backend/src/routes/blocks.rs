@@ -0,0 +1,207 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::blocks;
+use crate::models::UserCard;
+use crate::pagination::{PageQuery, TOTAL_ESTIMATE_CAP};
+use crate::privacy;
+use crate::routes::{internal_error, invalid_cursor, require_user, AppState};
+
+#[derive(Debug, Serialize)]
+struct ListedUser {
+    user: UserCard,
+    since: chrono::DateTime<chrono::Utc>,
+}
+
+async fn require_other_user(state: &AppState, current_user_id: Uuid, user_id: Uuid) -> Result<(), (StatusCode, String)> {
+    if current_user_id == user_id {
+        return Err((StatusCode::BAD_REQUEST, "You can't do that to yourself".to_string()));
+    }
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE id = $1)")
+        .bind(user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    if !exists {
+        return Err((StatusCode::NOT_FOUND, "User not found".to_string()));
+    }
+
+    Ok(())
+}
+
+/// One page of the users the caller blocked or muted, newest first, with a
+/// cursor for the next and an estimate of the total.
+async fn list(
+    state: &AppState,
+    table: &str,
+    owner_column: &str,
+    other_column: &str,
+    user_id: Uuid,
+    page: &PageQuery,
+) -> Result<(Vec<ListedUser>, Option<String>, i64), (StatusCode, String)> {
+    let after: Option<(chrono::DateTime<chrono::Utc>, Uuid)> = state.cursors
+        .decode(table, page)
+        .map_err(invalid_cursor)?;
+
+    let rows: Vec<(Uuid, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(&format!(
+        "SELECT {other}, created_at FROM {table}
+         WHERE {owner} = $1
+           AND ($2::timestamptz IS NULL OR (created_at, {other}) < ($2, $3))
+         ORDER BY created_at DESC, {other} DESC
+         LIMIT $4",
+        other = other_column, table = table, owner = owner_column
+    ))
+        .bind(user_id)
+        .bind(after.map(|(since, _)| since))
+        .bind(after.map(|(_, id)| id))
+        .bind(page.fetch_limit())
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let total_estimate: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM (SELECT 1 FROM {table} WHERE {owner} = $1 LIMIT $2) listed",
+        table = table, owner = owner_column
+    ))
+        .bind(user_id)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let (rows, next_cursor) = state.cursors.page(table, page, rows, |(id, since)| (*since, *id));
+
+    let ids: Vec<Uuid> = rows.iter().map(|(id, _)| *id).collect();
+    let cards = privacy::blocked_user_cards(&state.pool, &ids).await.map_err(internal_error)?;
+
+    let users = rows
+        .into_iter()
+        .filter_map(|(id, since)| cards.get(&id).cloned().map(|user| ListedUser { user, since }))
+        .collect();
+
+    Ok((users, next_cursor, total_estimate))
+}
+
+/// Blocks a user: any connection or open request between the two is removed
+/// and they stop seeing each other anywhere.
+pub async fn block_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    require_other_user(&state, current_user_id, user_id).await?;
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+    let created = blocks::block(&mut tx, current_user_id, user_id).await.map_err(internal_error)?;
+    tx.commit().await.map_err(internal_error)?;
+
+    let status = if created { StatusCode::CREATED } else { StatusCode::OK };
+    Ok::<_, (StatusCode, String)>((status, Json(serde_json::json!({
+        "message": "User blocked",
+        "user_id": user_id
+    }))))
+}
+
+pub async fn unblock_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    if !blocks::unblock(&state.pool, current_user_id, user_id).await.map_err(internal_error)? {
+        return Err((StatusCode::NOT_FOUND, "You haven't blocked this user".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_blocked(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let (blocked, next_cursor, total_estimate) = list(&state, "user_blocks", "blocker_id", "blocked_id", current_user_id, &page).await?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "blocked": blocked,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
+
+/// Mutes a user: their requests and profile views stop showing up for the
+/// caller. Nothing changes on their side.
+pub async fn mute_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    require_other_user(&state, current_user_id, user_id).await?;
+
+    let created = sqlx::query(
+        "INSERT INTO user_mutes (muter_id, muted_id) VALUES ($1, $2)
+         ON CONFLICT (muter_id, muted_id) DO NOTHING"
+    )
+        .bind(current_user_id)
+        .bind(user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(internal_error)?
+        .rows_affected() > 0;
+
+    let status = if created { StatusCode::CREATED } else { StatusCode::OK };
+    Ok::<_, (StatusCode, String)>((status, Json(serde_json::json!({
+        "message": "User muted",
+        "user_id": user_id
+    }))))
+}
+
+pub async fn unmute_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let result = sqlx::query("DELETE FROM user_mutes WHERE muter_id = $1 AND muted_id = $2")
+        .bind(current_user_id)
+        .bind(user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "You haven't muted this user".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_muted(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let (muted, next_cursor, total_estimate) = list(&state, "user_mutes", "muter_id", "muted_id", current_user_id, &page).await?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "muted": muted,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
@@ -4,21 +4,141 @@ use axum::{
     response::IntoResponse,
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
+use validator::Validate;
 
+use crate::completeness;
+use crate::contacts;
 use crate::routes::AppState;
-use crate::models::{ConnectionRequest, UpdateConnectionRequest};
-use crate::auth::middleware::{get_user_id_from_headers, Claims};
+use crate::models::{BulkRequestAction, BulkRequestUpdate, Connection, ConnectionRequest, ConnectionStatus, UpdateConnectionRequest, UserCard, VerificationTier};
+use crate::network::{self, NetworkDegree};
+use crate::pagination::{Cursors, PageQuery, TOTAL_ESTIMATE_CAP};
+use crate::privacy::{self, Field, Neighbourhood, ViewerAccess};
+use crate::relationships::{self, StatusError, RequestOutcome};
+use crate::spam;
+use crate::auth::middleware::get_user_id_from_headers;
 
 #[derive(Debug, Deserialize)]
 pub struct SearchQuery {
     pub q: Option<String>,
 }
 
+#[derive(sqlx::FromRow)]
+struct SearchMatch {
+    id: Uuid,
+    trust_score: Option<f64>,
+    /// Trust blended with profile completeness; results are ranked by it
+    signal: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchResult {
+    #[serde(flatten)]
+    user: UserCard,
+    trust_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    degree: Option<NetworkDegree>,
+}
+
+#[derive(sqlx::FromRow)]
+struct PendingRow {
+    id: Uuid,
+    sender_id: Uuid,
+    note: Option<String>,
+    requested_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct PendingRequest {
+    id: Uuid,
+    sender: UserCard,
+    note: Option<String>,
+    requested_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct InboxQuery {
+    #[serde(default)]
+    pub ignored: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct SentRequest {
+    id: Uuid,
+    receiver: UserCard,
+    note: Option<String>,
+    /// As the sender may know it: rejected and ignored requests show as pending
+    status: ConnectionStatus,
+    requested_at: chrono::DateTime<chrono::Utc>,
+    /// When a request that looks pending will expire
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConnectionEntry {
+    id: Uuid,
+    user: UserCard,
+    trust_score: Option<f64>,
+    connected_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// The request note and its reply, shown only to the two people involved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply: Option<String>,
+    /// The caller's own tags and notes, on their own list only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    private_note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionSort {
+    /// Newest connections first
+    #[default]
+    Recent,
+    Name,
+    /// Most recently seen or endorsed either way first
+    LastInteraction,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConnectionsQuery {
+    pub sort: Option<ConnectionSort>,
+    /// Only connections the caller gave this tag
+    pub tag: Option<String>,
+    /// Only names starting with this
+    pub name: Option<String>,
+    /// Only people currently working at a company whose name contains this
+    pub company: Option<String>,
+    pub tier: Option<String>,
+}
+
+/// A trimmed request note or reply, `None` when blank, or why it was refused.
+fn clean_message(text: Option<&str>) -> Result<Option<String>, &'static str> {
+    let Some(text) = text.map(str::trim).filter(|text| !text.is_empty()) else {
+        return Ok(None);
+    };
+
+    spam::check(text)?;
+    Ok(Some(text.to_string()))
+}
+
+/// JSON error for a cursor that doesn't belong to this listing.
+fn invalid_cursor() -> (StatusCode, Json<serde_json::Value>) {
+    (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+        "error": "Invalid cursor"
+    })))
+}
+
 pub async fn search_users(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(query): Query<SearchQuery>,
+    Query(page): Query<PageQuery>,
 ) -> impl IntoResponse {
     let search_term = query.q.unwrap_or_default();
 
@@ -28,16 +148,62 @@ pub async fn search_users(
         })));
     }
 
-    let users = match sqlx::query!(
+    let viewer_id = get_user_id_from_headers(&headers);
+
+    let scope = format!("search:{}", search_term);
+    let after: Option<(f64, Uuid)> = match state.cursors.decode(&scope, &page) {
+        Ok(after) => after,
+        Err(_) => return invalid_cursor(),
+    };
+
+    let neighbourhood = match Neighbourhood::load(&state.pool, viewer_id).await {
+        Ok(neighbourhood) => neighbourhood,
+        Err(e) => {
+            eprintln!("Database search error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to search users"
+            })));
+        }
+    };
+
+    // Complete profiles rank above equally trusted empty ones. Names match
+    // as the viewer sees them, so a search for a hidden last name doesn't
+    // find its owner, by name or by a handle that may spell it out. The
+    // viewer's place in the network is bound from $2.
+    let matches = format!(
         r#"
-        SELECT id, email, full_name, profile_picture_url
-        FROM users
-        WHERE full_name ILIKE $1 OR email ILIKE $1
-        ORDER BY full_name
-        LIMIT 10
+        WITH matches AS (
+            SELECT u.id, ts.score AS trust_score, {} AS signal
+            FROM users u
+            LEFT JOIN user_profiles p ON p.user_id = u.id
+            LEFT JOIN trust_scores ts ON ts.user_id = u.id
+            WHERE (({}) ILIKE $1 OR ({} AND u.handle ILIKE $1))
+              AND NOT EXISTS (
+                  SELECT 1 FROM user_blocks b
+                  WHERE (b.blocker_id = $2 AND b.blocked_id = u.id) OR (b.blocker_id = u.id AND b.blocked_id = $2)
+              )
+        )
         "#,
-        format!("%{}%", search_term)
-    )
+        completeness::ranking_signal_sql(),
+        privacy::display_name_sql("u.id", "u.full_name", 2),
+        privacy::visible_sql(Field::LastName, "u.id", 2)
+    );
+    let pattern = format!("%{}%", escape_like(&search_term));
+
+    let users: Vec<SearchMatch> = match sqlx::query_as(&format!(
+        "{} SELECT * FROM matches
+         WHERE $5::float8 IS NULL OR (signal, id) < ($5, $6)
+         ORDER BY signal DESC, id DESC
+         LIMIT $7",
+        matches
+    ))
+        .bind(&pattern)
+        .bind(neighbourhood.viewer_id)
+        .bind(&neighbourhood.first)
+        .bind(&neighbourhood.second)
+        .bind(after.map(|(signal, _)| signal))
+        .bind(after.map(|(_, id)| id))
+        .bind(page.fetch_limit())
         .fetch_all(&state.pool)
         .await {
         Ok(users) => users,
@@ -49,19 +215,62 @@ pub async fn search_users(
         }
     };
 
-    let user_results: Vec<serde_json::Value> = users.into_iter().map(|user| {
-        serde_json::json!({
-            "id": user.id,
-            "email": user.email,
-            "full_name": user.full_name,
-            "profile_picture_url": user.profile_picture_url,
-            "headline": "Professional"
-        })
-    }).collect();
+    let (users, next_cursor) = state.cursors.page(&scope, &page, users, |user| (user.signal, user.id));
+
+    let mut user_results: Vec<SearchResult> = match privacy::attach_cards(&state.pool, viewer_id, users, |user| user.id).await {
+        Ok(users) => users
+            .into_iter()
+            .map(|(user, card)| SearchResult { user: card, trust_score: user.trust_score, degree: None })
+            .collect(),
+        Err(e) => {
+            eprintln!("Database search error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to search users"
+            })));
+        }
+    };
+
+    let total_estimate: i64 = match sqlx::query_scalar(&format!(
+        "{} SELECT COUNT(*) FROM (SELECT 1 FROM matches LIMIT $5) capped",
+        matches
+    ))
+        .bind(&pattern)
+        .bind(neighbourhood.viewer_id)
+        .bind(&neighbourhood.first)
+        .bind(&neighbourhood.second)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(&state.pool)
+        .await {
+        Ok(total) => total,
+        Err(e) => {
+            eprintln!("Database search error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to search users"
+            })));
+        }
+    };
+
+    if let Some(viewer_id) = viewer_id {
+        let ids: Vec<Uuid> = user_results.iter().map(|result| result.user.id).collect();
+        match network::degrees(&state.pool, viewer_id, &ids, network::MAX_DISTANCE).await {
+            Ok(degrees) => {
+                for result in &mut user_results {
+                    result.degree = degrees.get(&result.user.id).copied();
+                }
+            },
+            Err(e) => {
+                eprintln!("Database search error: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                    "error": "Failed to search users"
+                })));
+            }
+        }
+    }
 
     (StatusCode::OK, Json(serde_json::json!({
         "users": user_results,
-        "count": user_results.len()
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
     })))
 }
 
@@ -87,6 +296,21 @@ pub async fn send_connection_request(
         })));
     }
 
+    if let Err(validation_errors) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": format!("Validation failed: {:?}", validation_errors)
+        })));
+    }
+
+    let note = match clean_message(payload.note.as_deref()) {
+        Ok(note) => note,
+        Err(reason) => {
+            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": reason
+            })));
+        }
+    };
+
     let _receiver_exists = match sqlx::query!(
         "SELECT id FROM users WHERE id = $1",
         payload.receiver_id
@@ -107,29 +331,60 @@ pub async fn send_connection_request(
         }
     };
 
-    match sqlx::query!(
-        r#"
-        INSERT INTO connections (sender_id, receiver_id, status)
-        VALUES ($1, $2, 'pending')
-        ON CONFLICT (sender_id, receiver_id) DO NOTHING
-        RETURNING id
-        "#,
-        current_user_id,
-        payload.receiver_id
-    )
-        .fetch_optional(&state.pool)
-        .await {
-        Ok(Some(connection)) => {
-            println!("✅ Connection request created: {}", connection.id);
+    let outcome = async {
+        let mut tx = state.pool.begin().await?;
+        let outcome = relationships::request_connection(
+            &mut tx,
+            &state.request_policy,
+            current_user_id,
+            payload.receiver_id,
+            note.as_deref(),
+        )
+            .await?;
+        tx.commit().await?;
+        Ok::<_, relationships::StatusError>(outcome)
+    }
+        .await;
+
+    match outcome {
+        Ok(RequestOutcome::Sent(connection_id)) => {
+            println!("✅ Connection request created: {}", connection_id);
             (StatusCode::OK, Json(serde_json::json!({
                 "message": "Connection request sent",
-                "connection_id": connection.id,
-                "status": "pending"
+                "connection_id": connection_id,
+                "status": ConnectionStatus::Pending
             })))
         },
-        Ok(None) => {
+        Ok(RequestOutcome::AutoAccepted(connection_id)) => {
+            println!("✅ Crossing requests accepted: {}", connection_id);
+            (StatusCode::OK, Json(serde_json::json!({
+                "message": "They had already asked to connect with you, so you're now connected",
+                "connection_id": connection_id,
+                "status": ConnectionStatus::Accepted
+            })))
+        },
+        Ok(RequestOutcome::AlreadyPending(connection_id)) => {
+            (StatusCode::CONFLICT, Json(serde_json::json!({
+                "error": "Connection request already exists",
+                "connection_id": connection_id
+            })))
+        },
+        // Indistinguishable from a missing user, so a block isn't revealed
+        Ok(RequestOutcome::Blocked) => {
+            (StatusCode::NOT_FOUND, Json(serde_json::json!({
+                "error": "User not found"
+            })))
+        },
+        Ok(RequestOutcome::CoolingDown(until)) => {
+            (StatusCode::TOO_MANY_REQUESTS, Json(serde_json::json!({
+                "error": "You're sending connection requests too quickly. Please try again later.",
+                "retry_after": until
+            })))
+        },
+        Ok(RequestOutcome::AlreadyConnected(connection_id)) => {
             (StatusCode::CONFLICT, Json(serde_json::json!({
-                "error": "Connection request already exists"
+                "error": "You are already connected",
+                "connection_id": connection_id
             })))
         },
         Err(e) => {
@@ -141,9 +396,13 @@ pub async fn send_connection_request(
     }
 }
 
+/// Incoming requests still waiting on the caller, or with `?ignored=true`
+/// the ones they set aside, which can still be accepted.
 pub async fn get_pending_requests(
     State(state): State<AppState>,
     headers: HeaderMap,
+    Query(inbox): Query<InboxQuery>,
+    Query(page): Query<PageQuery>,
 ) -> impl IntoResponse {
     let current_user_id = match get_user_id_from_headers(&headers) {
         Some(user_id) => user_id,
@@ -154,18 +413,69 @@ pub async fn get_pending_requests(
         }
     };
 
-    let requests = match sqlx::query!(
-        r#"
-        SELECT c.id, c.sender_id, u.full_name as sender_name, u.email as sender_email, c.created_at
-        FROM connections c
-        JOIN users u ON c.sender_id = u.id
-        WHERE c.receiver_id = $1 AND c.status = 'pending'
-        ORDER BY c.created_at DESC
-        "#,
-        current_user_id
-    )
-        .fetch_all(&state.pool)
-        .await {
+    let (status, scope) = if inbox.ignored {
+        (ConnectionStatus::Ignored, "ignored_requests")
+    } else {
+        (ConnectionStatus::Pending, "pending_requests")
+    };
+
+    let after: Option<(chrono::DateTime<chrono::Utc>, Uuid)> = match state.cursors.decode(scope, &page) {
+        Ok(after) => after,
+        Err(_) => return invalid_cursor(),
+    };
+
+    let result = async {
+        let requests: Vec<PendingRow> = sqlx::query_as(
+            r#"
+            SELECT c.id, c.sender_id, c.note, c.requested_at
+            FROM connections c
+            WHERE c.receiver_id = $1 AND c.status = $5
+              -- Requests from muted users wait unseen
+              AND NOT EXISTS (SELECT 1 FROM user_mutes m WHERE m.muter_id = $1 AND m.muted_id = c.sender_id)
+              AND ($2::timestamptz IS NULL OR (c.requested_at, c.id) < ($2, $3))
+            ORDER BY c.requested_at DESC, c.id DESC
+            LIMIT $4
+            "#
+        )
+            .bind(current_user_id)
+            .bind(after.map(|(requested_at, _)| requested_at))
+            .bind(after.map(|(_, id)| id))
+            .bind(page.fetch_limit())
+            .bind(status)
+            .fetch_all(&state.pool)
+            .await?;
+
+        let total_estimate: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM (
+                 SELECT 1 FROM connections c
+                 WHERE c.receiver_id = $1 AND c.status = $3
+                   AND NOT EXISTS (SELECT 1 FROM user_mutes m WHERE m.muter_id = $1 AND m.muted_id = c.sender_id)
+                 LIMIT $2
+             ) pending"
+        )
+            .bind(current_user_id)
+            .bind(TOTAL_ESTIMATE_CAP)
+            .bind(status)
+            .fetch_one(&state.pool)
+            .await?;
+
+        Ok::<_, sqlx::Error>((requests, total_estimate))
+    }
+        .await;
+
+    let (requests, total_estimate) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch connection requests"
+            })));
+        }
+    };
+
+    let (requests, next_cursor) = state.cursors.page(scope, &page, requests, |req| (req.requested_at, req.id));
+
+    let requests = match privacy::attach_cards(&state.pool, Some(current_user_id), requests, |req| req.sender_id).await {
         Ok(requests) => requests,
         Err(e) => {
             eprintln!("Database error: {}", e);
@@ -175,18 +485,19 @@ pub async fn get_pending_requests(
         }
     };
 
-    let request_results: Vec<serde_json::Value> = requests.into_iter().map(|req| {
-        serde_json::json!({
-            "id": req.id,
-            "sender_id": req.sender_id,
-            "sender_name": req.sender_name,
-            "sender_email": req.sender_email,
-            "created_at": req.created_at
-        })
+    let request_results: Vec<PendingRequest> = requests.into_iter().map(|(req, sender)| {
+        PendingRequest {
+            id: req.id,
+            sender,
+            note: req.note,
+            requested_at: req.requested_at,
+        }
     }).collect();
 
     (StatusCode::OK, Json(serde_json::json!({
-        "requests": request_results
+        "requests": request_results,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
     })))
 }
 
@@ -207,32 +518,63 @@ pub async fn update_connection_request(
 
     println!("User {} updating connection {} to status: {:?}", current_user_id, connection_id, payload.status);
 
-    match sqlx::query!(
-        r#"
-        UPDATE connections
-        SET status = $1, updated_at = NOW()
-        WHERE id = $2 AND receiver_id = $3
-        RETURNING id
-        "#,
-        payload.status.to_string(),
-        connection_id,
-        current_user_id
-    )
-        .fetch_optional(&state.pool)
-        .await {
-        Ok(Some(_)) => {
+    if let Err(validation_errors) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": format!("Validation failed: {:?}", validation_errors)
+        })));
+    }
+
+    let reply = match clean_message(payload.reply.as_deref()) {
+        Ok(reply) => reply,
+        Err(reason) => {
+            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": reason
+            })));
+        }
+    };
+
+    if !matches!(payload.status, ConnectionStatus::Accepted | ConnectionStatus::Rejected | ConnectionStatus::Ignored) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": "A request can only be accepted, rejected or ignored"
+        })));
+    }
+
+    if reply.is_some() && payload.status != ConnectionStatus::Accepted {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": "You can only reply when accepting a request"
+        })));
+    }
+
+    let result = async {
+        let mut tx = state.pool.begin().await?;
+        if let Err(e) = relationships::answer_request(&mut tx, connection_id, current_user_id, payload.status, reply.as_deref()).await {
+            return Ok(Err(e));
+        }
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(Ok(()))
+    }
+        .await;
+
+    match result {
+        Ok(Ok(())) => {
             (StatusCode::OK, Json(serde_json::json!({
                 "message": format!("Connection request {}", payload.status),
                 "connection_id": connection_id,
                 "status": payload.status
             })))
         },
-        Ok(None) => {
+        Ok(Err(StatusError::NotFound)) => {
             (StatusCode::NOT_FOUND, Json(serde_json::json!({
                 "error": "Connection request not found or you don't have permission"
             })))
         },
-        Err(e) => {
+        Ok(Err(StatusError::IllegalTransition(current))) => {
+            (StatusCode::CONFLICT, Json(serde_json::json!({
+                "error": format!("This request was already {}", current),
+                "status": current
+            })))
+        },
+        Ok(Err(StatusError::Database(e))) | Err(e) => {
             eprintln!("Database error: {}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
                 "error": "Failed to update connection request"
@@ -241,9 +583,13 @@ pub async fn update_connection_request(
     }
 }
 
-pub async fn get_connections(
+/// Requests the caller sent that haven't been accepted, newest first. Each
+/// shows the status the caller may know, so nothing here gives away a
+/// rejection or an ignore.
+pub async fn get_sent_requests(
     State(state): State<AppState>,
     headers: HeaderMap,
+    Query(page): Query<PageQuery>,
 ) -> impl IntoResponse {
     let current_user_id = match get_user_id_from_headers(&headers) {
         Some(user_id) => user_id,
@@ -254,49 +600,698 @@ pub async fn get_connections(
         }
     };
 
-    let connections = match sqlx::query!(
-        r#"
-        SELECT
-            c.id as connection_id,
-            u.id as user_id,
-            u.full_name,
-            u.email,
-            u.profile_picture_url,
-            c.created_at as connected_at
-        FROM connections c
-        JOIN users u ON (
-            (c.sender_id = $1 AND c.receiver_id = u.id) OR
-            (c.receiver_id = $1 AND c.sender_id = u.id)
+    let after: Option<(chrono::DateTime<chrono::Utc>, Uuid)> = match state.cursors.decode("sent_requests", &page) {
+        Ok(after) => after,
+        Err(_) => return invalid_cursor(),
+    };
+
+    let result = async {
+        let requests: Vec<Connection> = sqlx::query_as(
+            "SELECT * FROM connections c
+             WHERE c.sender_id = $1 AND c.status <> 'accepted'
+               AND ($2::timestamptz IS NULL OR (c.requested_at, c.id) < ($2, $3))
+             ORDER BY c.requested_at DESC, c.id DESC
+             LIMIT $4"
         )
-        WHERE c.status = 'accepted'
-        ORDER BY c.created_at DESC
-        "#,
-        current_user_id
-    )
-        .fetch_all(&state.pool)
-        .await {
-        Ok(connections) => connections,
+            .bind(current_user_id)
+            .bind(after.map(|(requested_at, _)| requested_at))
+            .bind(after.map(|(_, id)| id))
+            .bind(page.fetch_limit())
+            .fetch_all(&state.pool)
+            .await?;
+
+        let total_estimate: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM (
+                 SELECT 1 FROM connections WHERE sender_id = $1 AND status <> 'accepted' LIMIT $2
+             ) sent"
+        )
+            .bind(current_user_id)
+            .bind(TOTAL_ESTIMATE_CAP)
+            .fetch_one(&state.pool)
+            .await?;
+
+        let (requests, next_cursor) = state.cursors.page("sent_requests", &page, requests, |req| (req.requested_at, req.id));
+        let requests = privacy::attach_cards(&state.pool, Some(current_user_id), requests, |req| req.receiver_id).await?;
+
+        Ok::<_, sqlx::Error>((requests, next_cursor, total_estimate))
+    }
+        .await;
+
+    let (requests, next_cursor, total_estimate) = match result {
+        Ok(result) => result,
         Err(e) => {
             eprintln!("Database error: {}", e);
             return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
-                "error": "Failed to fetch connections"
+                "error": "Failed to fetch sent requests"
             })));
         }
     };
 
-    let connection_results: Vec<serde_json::Value> = connections.into_iter().map(|conn| {
-        serde_json::json!({
-            "id": conn.connection_id,
-            "user_id": conn.user_id,
-            "full_name": conn.full_name,
-            "email": conn.email,
-            "profile_picture_url": conn.profile_picture_url,
-            "connected_at": conn.connected_at
-        })
+    let expiry = state.request_policy.request_expiry;
+    let requests: Vec<SentRequest> = requests.into_iter().map(|(req, receiver)| {
+        let status = req.status_for_as_of(current_user_id, expiry);
+        SentRequest {
+            id: req.id,
+            receiver,
+            note: req.note,
+            expires_at: (status == ConnectionStatus::Pending).then(|| req.requested_at + expiry),
+            status,
+            requested_at: req.requested_at,
+        }
     }).collect();
 
     (StatusCode::OK, Json(serde_json::json!({
-        "connections": connection_results,
-        "count": connection_results.len()
+        "requests": requests,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
     })))
 }
+
+/// Accepts or ignores many incoming requests in one go. Requests that can't
+/// be answered are reported back rather than failing the rest.
+pub async fn bulk_update_requests(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<BulkRequestUpdate>,
+) -> impl IntoResponse {
+    let current_user_id = match get_user_id_from_headers(&headers) {
+        Some(user_id) => user_id,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({
+                "error": "Authentication required"
+            })));
+        }
+    };
+
+    if let Err(validation_errors) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": format!("Validation failed: {:?}", validation_errors)
+        })));
+    }
+
+    let next = match payload.action {
+        BulkRequestAction::Accept => ConnectionStatus::Accepted,
+        BulkRequestAction::Ignore => ConnectionStatus::Ignored,
+    };
+
+    let mut ids = payload.connection_ids;
+    ids.sort();
+    ids.dedup();
+
+    let result = async {
+        let mut tx = state.pool.begin().await?;
+        let mut updated = Vec::new();
+        let mut failed = Vec::new();
+
+        for id in ids {
+            match relationships::answer_request(&mut tx, id, current_user_id, next, None).await {
+                Ok(()) => updated.push(id),
+                Err(StatusError::NotFound) => failed.push(serde_json::json!({
+                    "connection_id": id,
+                    "error": "Connection request not found or you don't have permission"
+                })),
+                Err(StatusError::IllegalTransition(current)) => failed.push(serde_json::json!({
+                    "connection_id": id,
+                    "error": format!("This request was already {}", current),
+                    "status": current
+                })),
+                Err(StatusError::Database(e)) => return Err(e),
+            }
+        }
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>((updated, failed))
+    }
+        .await;
+
+    match result {
+        Ok((updated, failed)) => {
+            (StatusCode::OK, Json(serde_json::json!({
+                "status": next,
+                "updated": updated,
+                "failed": failed
+            })))
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to update connection requests"
+            })))
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ConnectionRow {
+    connection_id: Uuid,
+    user_id: Uuid,
+    sort_name: String,
+    trust_score: Option<f64>,
+    connected_at: Option<chrono::DateTime<chrono::Utc>>,
+    last_interaction_at: Option<chrono::DateTime<chrono::Utc>>,
+    note: Option<String>,
+    reply: Option<String>,
+}
+
+/// Where the previous page of connections ended, in whichever sort it used.
+#[derive(Debug, Serialize, Deserialize)]
+struct ConnectionCursor {
+    at: Option<chrono::DateTime<chrono::Utc>>,
+    name: Option<String>,
+    id: Uuid,
+}
+
+impl ConnectionSort {
+    fn scope(self) -> &'static str {
+        match self {
+            ConnectionSort::Recent => "connections:recent",
+            ConnectionSort::Name => "connections:name",
+            ConnectionSort::LastInteraction => "connections:last_interaction",
+        }
+    }
+
+    fn order_by(self) -> &'static str {
+        match self {
+            ConnectionSort::Recent => "connected_at DESC, connection_id DESC",
+            ConnectionSort::Name => "sort_name, connection_id",
+            ConnectionSort::LastInteraction => "last_interaction_at DESC, connection_id DESC",
+        }
+    }
+
+    /// Rows after the cursor in `$9` (time), `$10` (name) and `$11` (id).
+    fn after(self) -> &'static str {
+        match self {
+            ConnectionSort::Recent => "($9::timestamptz IS NULL OR (connected_at, connection_id) < ($9, $11))",
+            ConnectionSort::Name => "($10::text IS NULL OR (sort_name, connection_id) > ($10, $11))",
+            ConnectionSort::LastInteraction => {
+                "($9::timestamptz IS NULL OR (last_interaction_at, connection_id) < ($9, $11))"
+            }
+        }
+    }
+
+    fn cursor(self, row: &ConnectionRow) -> ConnectionCursor {
+        let (at, name) = match self {
+            ConnectionSort::Recent => (row.connected_at, None),
+            ConnectionSort::Name => (None, Some(row.sort_name.clone())),
+            ConnectionSort::LastInteraction => (row.last_interaction_at, None),
+        };
+        ConnectionCursor { at, name, id: row.connection_id }
+    }
+}
+
+/// `value` for use in a LIKE pattern, matching itself literally.
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// The filters and sort for one listing, checked.
+struct ConnectionFilters {
+    sort: ConnectionSort,
+    tag: Option<String>,
+    name_prefix: Option<String>,
+    company: Option<String>,
+    tier: Option<VerificationTier>,
+}
+
+impl ConnectionFilters {
+    /// Tags and interactions are the caller's own business, so they only
+    /// apply to the caller's own list.
+    fn parse(query: &ConnectionsQuery, own_list: bool) -> Result<Self, &'static str> {
+        let sort = query.sort.unwrap_or_default();
+        if !own_list && (sort == ConnectionSort::LastInteraction || query.tag.is_some()) {
+            return Err("Sorting by last interaction and filtering by tag only work on your own connections");
+        }
+
+        let tier = match query.tier.as_deref() {
+            Some(tier) => Some(VerificationTier::parse(tier).ok_or("Unknown verification tier")?),
+            None => None,
+        };
+
+        let non_empty = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+
+        Ok(Self {
+            sort,
+            tag: query.tag.as_deref().and_then(contacts::normalize_tag),
+            name_prefix: non_empty(&query.name),
+            company: non_empty(&query.company),
+            tier,
+        })
+    }
+}
+
+/// One page of `user_id`'s accepted connections, each shown as `viewer_id`
+/// may see them, with the cursor for the next page and about how many match.
+async fn list_connections(
+    pool: &sqlx::PgPool,
+    cursors: &Cursors,
+    viewer_id: Uuid,
+    user_id: Uuid,
+    filters: &ConnectionFilters,
+    page: &PageQuery,
+    after: Option<ConnectionCursor>,
+) -> Result<(Vec<ConnectionEntry>, Option<String>, i64), sqlx::Error> {
+    // Views and endorsements between the two count as interactions, but
+    // working them out is only worth it when sorting by them
+    let last_interaction = if filters.sort == ConnectionSort::LastInteraction {
+        "GREATEST(
+             c.updated_at,
+             (SELECT MAX(v.viewed_at) FROM profile_views v
+              WHERE (v.viewer_id = $1 AND v.viewed_id = u.id) OR (v.viewer_id = u.id AND v.viewed_id = $1)),
+             (SELECT MAX(e.created_at) FROM endorsements e
+              WHERE (e.endorser_id = $1 AND e.user_id = u.id) OR (e.endorser_id = u.id AND e.user_id = $1))
+         )"
+    } else {
+        "c.updated_at"
+    };
+
+    // Filters only match on what the viewer can see, a hidden last name or
+    // a company among hidden profile fields being out of reach, and they run
+    // here so the page, its cursor and the total agree. The viewer's place
+    // in the network is bound from $6.
+    let listed = format!(
+        r#"
+        WITH listed AS (
+            SELECT
+                c.id AS connection_id,
+                u.id AS user_id,
+                LOWER(u.full_name) AS sort_name,
+                ts.score AS trust_score,
+                c.accepted_at AS connected_at,
+                {} AS last_interaction_at,
+                c.note,
+                c.reply
+            FROM connections c
+            JOIN users u ON (
+                (c.sender_id = $1 AND c.receiver_id = u.id) OR
+                (c.receiver_id = $1 AND c.sender_id = u.id)
+            )
+            LEFT JOIN trust_scores ts ON ts.user_id = u.id
+            WHERE c.status = 'accepted'
+              AND NOT EXISTS (
+                  SELECT 1 FROM user_blocks b
+                  WHERE (b.blocker_id = $6 AND b.blocked_id = u.id) OR (b.blocker_id = u.id AND b.blocked_id = $6)
+              )
+              AND ($2::varchar IS NULL OR EXISTS (
+                  SELECT 1 FROM connection_tags t WHERE t.owner_id = $1 AND t.contact_id = u.id AND t.tag = $2
+              ))
+              AND ($3::varchar IS NULL OR ({}) ILIKE $3)
+              AND ($4::varchar IS NULL OR ({} AND EXISTS (
+                  SELECT 1 FROM positions p WHERE p.user_id = u.id AND p.is_current AND p.company_name ILIKE $4
+              )))
+              AND ($5::varchar IS NULL OR u.verification_tier = $5)
+        )
+        "#,
+        last_interaction,
+        privacy::display_name_sql("u.id", "u.full_name", 6),
+        privacy::visible_sql(Field::ProfileFields, "u.id", 6)
+    );
+    let neighbourhood = Neighbourhood::load(pool, Some(viewer_id)).await?;
+
+    let name_pattern = filters.name_prefix.as_deref().map(|prefix| format!("{}%", escape_like(prefix)));
+    let company_pattern = filters.company.as_deref().map(|company| format!("%{}%", escape_like(company)));
+    let tier = filters.tier.map(|tier| tier.as_str());
+
+    let rows: Vec<ConnectionRow> = sqlx::query_as(&format!(
+        "{} SELECT * FROM listed WHERE {} ORDER BY {} LIMIT $12",
+        listed,
+        filters.sort.after(),
+        filters.sort.order_by()
+    ))
+        .bind(user_id)
+        .bind(&filters.tag)
+        .bind(&name_pattern)
+        .bind(&company_pattern)
+        .bind(tier)
+        .bind(neighbourhood.viewer_id)
+        .bind(&neighbourhood.first)
+        .bind(&neighbourhood.second)
+        .bind(after.as_ref().and_then(|after| after.at))
+        .bind(after.as_ref().and_then(|after| after.name.clone()))
+        .bind(after.as_ref().map(|after| after.id))
+        .bind(page.fetch_limit())
+        .fetch_all(pool)
+        .await?;
+
+    let total_estimate: i64 = sqlx::query_scalar(&format!(
+        "{} SELECT COUNT(*) FROM (SELECT 1 FROM listed LIMIT $9) capped",
+        listed
+    ))
+        .bind(user_id)
+        .bind(&filters.tag)
+        .bind(&name_pattern)
+        .bind(&company_pattern)
+        .bind(tier)
+        .bind(neighbourhood.viewer_id)
+        .bind(&neighbourhood.first)
+        .bind(&neighbourhood.second)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(pool)
+        .await?;
+
+    let (rows, next_cursor) = cursors.page(filters.sort.scope(), page, rows, |row| filters.sort.cursor(row));
+
+    let ids: Vec<Uuid> = rows.iter().map(|row| row.user_id).collect();
+    let connections = privacy::attach_cards(pool, Some(viewer_id), rows, |row| row.user_id).await?;
+    let own_list = viewer_id == user_id;
+
+    let (mut tags, mut notes) = if own_list {
+        (contacts::tags_by_contact(pool, user_id, &ids).await?, contacts::notes_by_contact(pool, user_id, &ids).await?)
+    } else {
+        (HashMap::new(), HashMap::new())
+    };
+
+    let entries = connections.into_iter()
+        .map(|(row, user)| {
+            ConnectionEntry {
+                tags: own_list.then(|| tags.remove(&row.user_id).unwrap_or_default()),
+                private_note: notes.remove(&row.user_id),
+                id: row.connection_id,
+                user,
+                trust_score: row.trust_score,
+                connected_at: row.connected_at,
+                note: row.note.filter(|_| own_list),
+                reply: row.reply.filter(|_| own_list),
+            }
+        })
+        .collect();
+
+    Ok((entries, next_cursor, total_estimate))
+}
+
+/// The caller's connections, a page at a time.
+pub async fn get_connections(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ConnectionsQuery>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = match get_user_id_from_headers(&headers) {
+        Some(user_id) => user_id,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({
+                "error": "Authentication required"
+            })));
+        }
+    };
+
+    connections_page(&state, current_user_id, current_user_id, &query, &page).await
+}
+
+/// Another user's connections, if their connection list setting allows it.
+pub async fn get_user_connections(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<ConnectionsQuery>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = match get_user_id_from_headers(&headers) {
+        Some(user_id) => user_id,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({
+                "error": "Authentication required"
+            })));
+        }
+    };
+
+    let visible = match ViewerAccess::load(&state.pool, Some(current_user_id), &[user_id]).await {
+        Ok(access) => access.can_see(user_id, Field::ConnectionList),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch connections"
+            })));
+        }
+    };
+
+    if !visible {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({
+            "error": "This connection list is private"
+        })));
+    }
+
+    connections_page(&state, current_user_id, user_id, &query, &page).await
+}
+
+async fn connections_page(
+    state: &AppState,
+    viewer_id: Uuid,
+    user_id: Uuid,
+    query: &ConnectionsQuery,
+    page: &PageQuery,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let filters = match ConnectionFilters::parse(query, viewer_id == user_id) {
+        Ok(filters) => filters,
+        Err(reason) => {
+            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": reason
+            })));
+        }
+    };
+
+    let after = match state.cursors.decode(filters.sort.scope(), page) {
+        Ok(after) => after,
+        Err(_) => return invalid_cursor(),
+    };
+
+    match list_connections(&state.pool, &state.cursors, viewer_id, user_id, &filters, page, after).await {
+        Ok((connections, next_cursor, total_estimate)) => {
+            (StatusCode::OK, Json(serde_json::json!({
+                "connections": connections,
+                "next_cursor": next_cursor,
+                "total_estimate": total_estimate
+            })))
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch connections"
+            })))
+        }
+    }
+}
+
+/// Lets the sender take back a request the receiver hasn't accepted. Rejected
+/// and ignored requests look pending to their sender, so those can be
+/// withdrawn too, and expired ones cleared away.
+pub async fn withdraw_connection_request(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = match get_user_id_from_headers(&headers) {
+        Some(user_id) => user_id,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({
+                "error": "Authentication required"
+            })));
+        }
+    };
+
+    let withdrawn = sqlx::query(
+        "DELETE FROM connections WHERE id = $1 AND sender_id = $2 AND status <> 'accepted'"
+    )
+        .bind(connection_id)
+        .bind(current_user_id)
+        .execute(&state.pool)
+        .await;
+
+    match withdrawn {
+        Ok(result) if result.rows_affected() > 0 => {
+            (StatusCode::OK, Json(serde_json::json!({
+                "message": "Connection request withdrawn",
+                "connection_id": connection_id
+            })))
+        },
+        Ok(_) => {
+            (StatusCode::NOT_FOUND, Json(serde_json::json!({
+                "error": "Pending request not found or you didn't send it"
+            })))
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to withdraw connection request"
+            })))
+        }
+    }
+}
+
+/// Ends an accepted connection. Either side may do it; endorsements, vouches
+/// and recommendations between the two go with it.
+pub async fn remove_connection(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = match get_user_id_from_headers(&headers) {
+        Some(user_id) => user_id,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({
+                "error": "Authentication required"
+            })));
+        }
+    };
+
+    let result = async {
+        let mut tx = state.pool.begin().await?;
+        // Waits out a request or block between the two that's in flight
+        relationships::lock_pair(&mut tx, current_user_id, user_id).await?;
+        let summary = relationships::disconnect(&mut tx, current_user_id, user_id).await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(summary)
+    }
+        .await;
+
+    match result {
+        Ok(Some(summary)) => {
+            println!("User {} disconnected from {}", current_user_id, user_id);
+            (StatusCode::OK, Json(serde_json::json!({
+                "message": "Connection removed",
+                "user_id": user_id,
+                "cleanup": summary
+            })))
+        },
+        Ok(None) => {
+            (StatusCode::NOT_FOUND, Json(serde_json::json!({
+                "error": "You are not connected to this user"
+            })))
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to remove connection"
+            })))
+        }
+    }
+}
+
+/// Accepted connections the caller shares with `user_id`. A mutual
+/// connection is left out when the viewer may not see their link to
+/// `user_id`.
+pub async fn get_mutual_connections(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = match get_user_id_from_headers(&headers) {
+        Some(user_id) => user_id,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({
+                "error": "Authentication required"
+            })));
+        }
+    };
+
+    let after: Option<Uuid> = match state.cursors.decode("mutual_connections", &page) {
+        Ok(after) => after,
+        Err(_) => return invalid_cursor(),
+    };
+
+    let result = async {
+        if privacy::user_cards(&state.pool, Some(current_user_id), &[user_id]).await?.is_empty() {
+            return Ok(None);
+        }
+
+        // Which edges the caller may see is decided in memory, so the whole
+        // list is filtered before it's paged, in id order
+        let mutual = network::mutual_connections(&state.pool, current_user_id, user_id).await?;
+        let access = ViewerAccess::load(&state.pool, Some(current_user_id), &[&mutual[..], &[user_id]].concat()).await?;
+        let visible: Vec<Uuid> = mutual
+            .into_iter()
+            .filter(|&id| network::edge_visible(&access, current_user_id, id, user_id))
+            .collect();
+        let total_estimate = (visible.len() as i64).min(TOTAL_ESTIMATE_CAP);
+
+        let rest: Vec<Uuid> = visible
+            .into_iter()
+            .filter(|&id| after.is_none_or(|after| id > after))
+            .take(page.fetch_limit() as usize)
+            .collect();
+        let (ids, next_cursor) = state.cursors.page("mutual_connections", &page, rest, |&id| id);
+
+        let cards = privacy::user_cards(&state.pool, Some(current_user_id), &ids).await?;
+        let mutual: Vec<_> = ids.into_iter().filter_map(|id| cards.get(&id).cloned()).collect();
+        Ok::<_, sqlx::Error>(Some((mutual, next_cursor, total_estimate)))
+    }
+        .await;
+
+    match result {
+        Ok(Some((mutual, next_cursor, total_estimate))) => {
+            (StatusCode::OK, Json(serde_json::json!({
+                "user_id": user_id,
+                "mutual": mutual,
+                "next_cursor": next_cursor,
+                "total_estimate": total_estimate
+            })))
+        },
+        Ok(None) => {
+            (StatusCode::NOT_FOUND, Json(serde_json::json!({
+                "error": "User not found"
+            })))
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch mutual connections"
+            })))
+        }
+    }
+}
+
+/// How far `user_id` is from the caller: 1st, 2nd, 3rd or out of network,
+/// with the people on one shortest path between them. The path is null when
+/// every shortest path runs through a connection the caller can't see.
+pub async fn get_connection_degree(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = match get_user_id_from_headers(&headers) {
+        Some(user_id) => user_id,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({
+                "error": "Authentication required"
+            })));
+        }
+    };
+
+    let result = async {
+        if privacy::user_cards(&state.pool, Some(current_user_id), &[user_id]).await?.is_empty() {
+            return Ok(None);
+        }
+
+        let (degree, path) = network::degree_with_path(&state.pool, current_user_id, user_id).await?;
+        let path = match path {
+            Some(ids) => {
+                let cards = privacy::user_cards(&state.pool, Some(current_user_id), &ids).await?;
+                Some(ids.into_iter().filter_map(|id| cards.get(&id).cloned()).collect::<Vec<_>>())
+            },
+            None => None,
+        };
+        Ok::<_, sqlx::Error>(Some((degree, path)))
+    }
+        .await;
+
+    match result {
+        Ok(Some((degree, path))) => {
+            (StatusCode::OK, Json(serde_json::json!({
+                "user_id": user_id,
+                "degree": degree,
+                "path": path
+            })))
+        },
+        Ok(None) => {
+            (StatusCode::NOT_FOUND, Json(serde_json::json!({
+                "error": "User not found"
+            })))
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to work out the connection degree"
+            })))
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::contacts;
+use crate::models::{SetConnectionTagsRequest, SetPrivateNoteRequest};
+use crate::pagination::PageQuery;
+use crate::relationships;
+use crate::routes::{internal_error, invalid_cursor, require_user, AppState};
+
+/// Tags and notes only go on accepted connections.
+async fn require_connection(state: &AppState, owner_id: Uuid, contact_id: Uuid) -> Result<(), (StatusCode, String)> {
+    if !relationships::are_connected(&state.pool, owner_id, contact_id).await.map_err(internal_error)? {
+        return Err((StatusCode::NOT_FOUND, "Connection not found".to_string()));
+    }
+
+    Ok(())
+}
+
+pub async fn set_connection_tags(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<SetConnectionTagsRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+
+    let mut tags = Vec::new();
+    for tag in &payload.tags {
+        let tag = contacts::normalize_tag(tag).ok_or((
+            StatusCode::BAD_REQUEST,
+            format!("Tags must be 1 to {} characters", contacts::MAX_TAG_LENGTH),
+        ))?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags.sort();
+
+    require_connection(&state, current_user_id, user_id).await?;
+
+    contacts::set_tags(&state.pool, current_user_id, user_id, &tags)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(serde_json::json!({
+        "user_id": user_id,
+        "tags": tags
+    })))
+}
+
+pub async fn remove_connection_tag(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((user_id, tag)): Path<(Uuid, String)>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let removed = match contacts::normalize_tag(&tag) {
+        Some(tag) => contacts::remove_tag(&state.pool, current_user_id, user_id, &tag)
+            .await
+            .map_err(internal_error)?,
+        None => false,
+    };
+
+    if !removed {
+        return Err((StatusCode::NOT_FOUND, "Tag not found on this connection".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn set_private_note(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<SetPrivateNoteRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+
+    require_connection(&state, current_user_id, user_id).await?;
+
+    let note = payload.note.as_deref().map(str::trim).filter(|note| !note.is_empty());
+    contacts::set_note(&state.pool, current_user_id, user_id, note)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(serde_json::json!({
+        "user_id": user_id,
+        "private_note": note
+    })))
+}
+
+/// Each of the caller's tags with how many connections carry it.
+pub async fn list_tag_counts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    let after = state.cursors.decode("tag_counts", &page).map_err(invalid_cursor)?;
+
+    let tags = contacts::tag_counts(&state.pool, current_user_id, after, page.fetch_limit())
+        .await
+        .map_err(internal_error)?;
+    let total_estimate = contacts::tag_total_estimate(&state.pool, current_user_id)
+        .await
+        .map_err(internal_error)?;
+    let (tags, next_cursor) = state.cursors.page("tag_counts", &page, tags, |tag| (tag.count, tag.tag.clone()));
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "tags": tags,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
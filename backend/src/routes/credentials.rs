@@ -0,0 +1,190 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::middleware::get_user_id_from_headers;
+use crate::credentials::{self, status_list};
+use crate::models::{VerifiableCredential, VerificationTier, VerifyCredentialRequest, VerifyCredentialResponse};
+use crate::routes::AppState;
+
+const STATUS_LIST_VALIDITY_HOURS: i64 = 24;
+
+pub async fn did_document(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "application/did+json")],
+        Json(state.issuer.did_document()),
+    )
+}
+
+pub async fn list_credentials(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current_user_id = get_user_id_from_headers(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))?;
+
+    let credentials: Vec<VerifiableCredential> = sqlx::query_as(
+        "SELECT * FROM verifiable_credentials WHERE user_id = $1 ORDER BY issued_at DESC"
+    )
+        .bind(current_user_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "issuer": state.issuer.did(),
+        "credentials": credentials
+    })))
+}
+
+/// A credential for the tier the caller currently holds. One that's still
+/// valid is returned as is; a new one is only minted when there's none.
+pub async fn issue_tier_credential(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current_user_id = get_user_id_from_headers(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))?;
+
+    let (tier,): (String,) = sqlx::query_as("SELECT verification_tier FROM users WHERE id = $1")
+        .bind(current_user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    let tier = VerificationTier::parse(&tier)
+        .ok_or((StatusCode::CONFLICT, format!("Unknown verification tier: {}", tier)))?;
+
+    let (credential, minted) = credentials::ensure_tier(&state.pool, &state.issuer, current_user_id, tier)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let status = if minted { StatusCode::CREATED } else { StatusCode::OK };
+    Ok::<_, (StatusCode, String)>((status, Json(credential)))
+}
+
+pub async fn revoke_credential(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(credential_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = get_user_id_from_headers(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))?;
+
+    let credential: VerifiableCredential = sqlx::query_as(
+        "UPDATE verifiable_credentials
+         SET revoked_at = COALESCE(revoked_at, NOW())
+         WHERE id = $1 AND user_id = $2
+         RETURNING *"
+    )
+        .bind(credential_id)
+        .bind(current_user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Credential not found".to_string()))?;
+
+    Ok::<_, (StatusCode, String)>(Json(credential))
+}
+
+/// Serves the signed StatusList2021 credential referenced by `credentialStatus`.
+pub async fn get_status_list(
+    State(state): State<AppState>,
+    Path(list_id): Path<i32>,
+) -> impl IntoResponse {
+    let (allocated,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS(SELECT 1 FROM verifiable_credentials WHERE status_list_id = $1)"
+    )
+        .bind(list_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if !allocated {
+        return Err((StatusCode::NOT_FOUND, "Status list not found".to_string()));
+    }
+
+    let revoked_indices: Vec<i32> = sqlx::query_scalar(
+        "SELECT status_list_index FROM verifiable_credentials
+         WHERE status_list_id = $1 AND revoked_at IS NOT NULL"
+    )
+        .bind(list_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let encoded_list = status_list::encode(&revoked_indices)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let issued_at = chrono::Utc::now();
+    let jwt = state.issuer
+        .issue_status_list_credential(
+            list_id,
+            encoded_list,
+            issued_at,
+            issued_at + chrono::Duration::hours(STATUS_LIST_VALIDITY_HOURS),
+        )
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(([(header::CONTENT_TYPE, "application/vc+jwt")], jwt))
+}
+
+/// Checks signature, expiry and revocation status of a presented JWT-VC.
+pub async fn verify_credential(
+    State(state): State<AppState>,
+    Json(payload): Json<VerifyCredentialRequest>,
+) -> impl IntoResponse {
+    let claims = match state.issuer.verify(payload.credential.trim()) {
+        Ok(claims) => claims,
+        Err(e) => {
+            return Ok(Json(VerifyCredentialResponse {
+                valid: false,
+                revoked: false,
+                subject: None,
+                verification_tier: None,
+                expires_at: None,
+                error: Some(e.to_string()),
+            }));
+        }
+    };
+
+    let credential_id = claims.jti
+        .strip_prefix("urn:uuid:")
+        .and_then(|id| Uuid::parse_str(id).ok());
+
+    let credential: Option<VerifiableCredential> = match credential_id {
+        Some(id) => sqlx::query_as("SELECT * FROM verifiable_credentials WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+        None => None,
+    };
+
+    let Some(credential) = credential else {
+        return Ok(Json(VerifyCredentialResponse {
+            valid: false,
+            revoked: false,
+            subject: None,
+            verification_tier: None,
+            expires_at: None,
+            error: Some("Credential was not issued by this service".to_string()),
+        }));
+    };
+
+    let revoked = credential.revoked_at.is_some();
+
+    Ok::<_, (StatusCode, String)>(Json(VerifyCredentialResponse {
+        valid: !revoked,
+        revoked,
+        subject: Some(credential.user_id),
+        verification_tier: Some(credential.verification_tier),
+        expires_at: Some(credential.expires_at),
+        error: revoked.then(|| "Credential has been revoked".to_string()),
+    }))
+}
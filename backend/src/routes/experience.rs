@@ -0,0 +1,392 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::PgConnection;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{Education, EducationRequest, Organization, Position, PositionRequest, ReorderRequest};
+use crate::organizations::{self, OrganizationKind};
+use crate::routes::{internal_error, require_user, AppState};
+
+pub async fn list_user_positions(pool: &sqlx::PgPool, user_id: Uuid) -> Result<Vec<Position>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM positions WHERE user_id = $1 ORDER BY sort_order, start_year DESC, start_month DESC")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn list_user_education(pool: &sqlx::PgPool, user_id: Uuid) -> Result<Vec<Education>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM education WHERE user_id = $1 ORDER BY sort_order, start_year DESC NULLS LAST")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Adds a position at the end of the user's list. `position` must already be validated.
+pub async fn insert_position(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    position: &PositionRequest,
+) -> Result<Position, sqlx::Error> {
+    let organization_id = organizations::resolve(conn, OrganizationKind::Company, &position.company_name).await?;
+
+    sqlx::query_as(
+        "INSERT INTO positions
+            (user_id, organization_id, company_name, title, start_year, start_month,
+             end_year, end_month, is_current, description, sort_order)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+                 (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM positions WHERE user_id = $1))
+         RETURNING *"
+    )
+        .bind(user_id)
+        .bind(organization_id)
+        .bind(position.company_name.trim())
+        .bind(position.title.trim())
+        .bind(position.start_year)
+        .bind(position.start_month)
+        .bind(position.end_year)
+        .bind(position.end_month)
+        .bind(position.is_current)
+        .bind(&position.description)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+/// Adds an education entry at the end of the user's list. `education` must already be validated.
+pub async fn insert_education(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    education: &EducationRequest,
+) -> Result<Education, sqlx::Error> {
+    let organization_id = organizations::resolve(conn, OrganizationKind::School, &education.school_name).await?;
+
+    sqlx::query_as(
+        "INSERT INTO education
+            (user_id, organization_id, school_name, degree, field_of_study, start_year, end_year, sort_order)
+         VALUES ($1, $2, $3, $4, $5, $6, $7,
+                 (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM education WHERE user_id = $1))
+         RETURNING *"
+    )
+        .bind(user_id)
+        .bind(organization_id)
+        .bind(education.school_name.trim())
+        .bind(&education.degree)
+        .bind(&education.field_of_study)
+        .bind(education.start_year)
+        .bind(education.end_year)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+/// Rewrites `sort_order` for `table` so it follows `ids`, which must list
+/// exactly the caller's entries.
+async fn reorder(
+    pool: &sqlx::PgPool,
+    table: &str,
+    user_id: Uuid,
+    ids: &[Uuid],
+) -> Result<(), (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(internal_error)?;
+
+    let mut existing: Vec<Uuid> = sqlx::query_scalar(&format!("SELECT id FROM {} WHERE user_id = $1 FOR UPDATE", table))
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    let mut requested = ids.to_vec();
+    existing.sort();
+    requested.sort();
+    if existing != requested {
+        return Err((StatusCode::BAD_REQUEST, "ids must list each of your entries exactly once".to_string()));
+    }
+
+    sqlx::query(&format!(
+        "UPDATE {} t SET sort_order = o.position - 1
+         FROM UNNEST($1::uuid[]) WITH ORDINALITY AS o(id, position)
+         WHERE t.id = o.id AND t.user_id = $2",
+        table
+    ))
+        .bind(ids)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok(())
+}
+
+pub async fn list_positions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let positions = list_user_positions(&state.pool, current_user_id)
+        .await
+        .map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({ "positions": positions })))
+}
+
+pub async fn create_position(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<PositionRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let payload = payload.trimmed();
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let position = insert_position(&mut tx, current_user_id, &payload)
+        .await
+        .map_err(internal_error)?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok((StatusCode::CREATED, Json(position)))
+}
+
+pub async fn update_position(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(position_id): Path<Uuid>,
+    Json(payload): Json<PositionRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let payload = payload.trimmed();
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let organization_id = organizations::resolve(&mut tx, OrganizationKind::Company, &payload.company_name)
+        .await
+        .map_err(internal_error)?;
+
+    let position: Position = sqlx::query_as(
+        "UPDATE positions SET
+            organization_id = $3, company_name = $4, title = $5, start_year = $6, start_month = $7,
+            end_year = $8, end_month = $9, is_current = $10, description = $11
+         WHERE id = $1 AND user_id = $2
+         RETURNING *"
+    )
+        .bind(position_id)
+        .bind(current_user_id)
+        .bind(organization_id)
+        .bind(payload.company_name.trim())
+        .bind(payload.title.trim())
+        .bind(payload.start_year)
+        .bind(payload.start_month)
+        .bind(payload.end_year)
+        .bind(payload.end_month)
+        .bind(payload.is_current)
+        .bind(&payload.description)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, "Position not found".to_string()))?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok(Json(position))
+}
+
+pub async fn delete_position(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(position_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let result = sqlx::query("DELETE FROM positions WHERE id = $1 AND user_id = $2")
+        .bind(position_id)
+        .bind(current_user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Position not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn reorder_positions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<ReorderRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    reorder(&state.pool, "positions", current_user_id, &payload.ids).await?;
+
+    let positions = list_user_positions(&state.pool, current_user_id)
+        .await
+        .map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({ "positions": positions })))
+}
+
+pub async fn list_education(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let education = list_user_education(&state.pool, current_user_id)
+        .await
+        .map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({ "education": education })))
+}
+
+pub async fn create_education(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<EducationRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let payload = payload.trimmed();
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let education = insert_education(&mut tx, current_user_id, &payload)
+        .await
+        .map_err(internal_error)?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok((StatusCode::CREATED, Json(education)))
+}
+
+pub async fn update_education(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(education_id): Path<Uuid>,
+    Json(payload): Json<EducationRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let payload = payload.trimmed();
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let organization_id = organizations::resolve(&mut tx, OrganizationKind::School, &payload.school_name)
+        .await
+        .map_err(internal_error)?;
+
+    let education: Education = sqlx::query_as(
+        "UPDATE education SET
+            organization_id = $3, school_name = $4, degree = $5, field_of_study = $6,
+            start_year = $7, end_year = $8
+         WHERE id = $1 AND user_id = $2
+         RETURNING *"
+    )
+        .bind(education_id)
+        .bind(current_user_id)
+        .bind(organization_id)
+        .bind(payload.school_name.trim())
+        .bind(&payload.degree)
+        .bind(&payload.field_of_study)
+        .bind(payload.start_year)
+        .bind(payload.end_year)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, "Education entry not found".to_string()))?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok(Json(education))
+}
+
+pub async fn delete_education(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(education_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let result = sqlx::query("DELETE FROM education WHERE id = $1 AND user_id = $2")
+        .bind(education_id)
+        .bind(current_user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Education entry not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn reorder_education(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<ReorderRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    reorder(&state.pool, "education", current_user_id, &payload.ids).await?;
+
+    let education = list_user_education(&state.pool, current_user_id)
+        .await
+        .map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({ "education": education })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrganizationQuery {
+    pub q: Option<String>,
+    pub kind: Option<String>,
+}
+
+/// Autocomplete for company and school names, so entries link to one record.
+pub async fn search_organizations(
+    State(state): State<AppState>,
+    Query(query): Query<OrganizationQuery>,
+) -> impl IntoResponse {
+    let normalized = organizations::normalize_name(&query.q.unwrap_or_default());
+    if normalized.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Search query is required".to_string()));
+    }
+
+    let organizations: Vec<Organization> = sqlx::query_as(
+        "SELECT * FROM organizations
+         WHERE normalized_name LIKE $1 AND ($2::varchar IS NULL OR kind = $2)
+         ORDER BY LENGTH(normalized_name), name
+         LIMIT 10"
+    )
+        .bind(format!("{}%", normalized))
+        .bind(query.kind)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(serde_json::json!({ "organizations": organizations })))
+}
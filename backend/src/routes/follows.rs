@@ -0,0 +1,220 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::blocks;
+use crate::follows;
+use crate::models::{CreatorModeRequest, FollowEdge, FollowListEntry, FollowSource};
+use crate::pagination::PageQuery;
+use crate::privacy::{self, Field, ViewerAccess};
+use crate::routes::{internal_error, invalid_cursor, require_user, AppState};
+
+/// Cards for a follow list, keyed by whichever end `other` picks.
+async fn list_entries(
+    state: &AppState,
+    viewer_id: Uuid,
+    edges: Vec<FollowEdge>,
+    other: fn(&FollowEdge) -> Uuid,
+) -> Result<Vec<FollowListEntry>, (StatusCode, String)> {
+    Ok(privacy::attach_cards(&state.pool, Some(viewer_id), edges, other)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .map(|(edge, user)| FollowListEntry { user, since: edge.since, source: edge.source })
+        .collect())
+}
+
+/// Another user's follow lists are shown to whoever may see their connection list.
+async fn require_visible_lists(state: &AppState, viewer_id: Uuid, user_id: Uuid) -> Result<(), (StatusCode, String)> {
+    if viewer_id == user_id {
+        return Ok(());
+    }
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE id = $1)")
+        .bind(user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let access = ViewerAccess::load(&state.pool, Some(viewer_id), &[user_id])
+        .await
+        .map_err(internal_error)?;
+
+    if !exists || access.is_blocked(user_id) {
+        return Err((StatusCode::NOT_FOUND, "User not found".to_string()));
+    }
+
+    if !access.can_see(user_id, Field::ConnectionList) {
+        return Err((StatusCode::FORBIDDEN, "This user's followers and following are private".to_string()));
+    }
+
+    Ok(())
+}
+
+async fn followers_of(
+    state: &AppState,
+    viewer_id: Uuid,
+    user_id: Uuid,
+    page: &PageQuery,
+) -> Result<serde_json::Value, (StatusCode, String)> {
+    let after = state.cursors.decode("followers", page).map_err(invalid_cursor)?;
+    let edges = follows::followers(&state.pool, user_id, after, page.fetch_limit()).await.map_err(internal_error)?;
+    let (edges, next_cursor) = state.cursors.page("followers", page, edges, |edge| (edge.since, edge.follower_id));
+
+    let followers = list_entries(state, viewer_id, edges, |edge| edge.follower_id).await?;
+    let total_estimate = follows::total_estimate(&state.pool, user_id, "followed_id").await.map_err(internal_error)?;
+
+    Ok(serde_json::json!({
+        "followers": followers,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    }))
+}
+
+async fn following_of(
+    state: &AppState,
+    viewer_id: Uuid,
+    user_id: Uuid,
+    page: &PageQuery,
+) -> Result<serde_json::Value, (StatusCode, String)> {
+    let after = state.cursors.decode("following", page).map_err(invalid_cursor)?;
+    let edges = follows::following(&state.pool, user_id, after, page.fetch_limit()).await.map_err(internal_error)?;
+    let (edges, next_cursor) = state.cursors.page("following", page, edges, |edge| (edge.since, edge.followed_id));
+
+    let following = list_entries(state, viewer_id, edges, |edge| edge.followed_id).await?;
+    let total_estimate = follows::total_estimate(&state.pool, user_id, "follower_id").await.map_err(internal_error)?;
+
+    Ok(serde_json::json!({
+        "following": following,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    }))
+}
+
+pub async fn follow_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    if current_user_id == user_id {
+        return Err((StatusCode::BAD_REQUEST, "You can't follow yourself".to_string()));
+    }
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE id = $1)")
+        .bind(user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let mut conn = state.pool.acquire().await.map_err(internal_error)?;
+    // Indistinguishable from a missing user, so a block isn't revealed
+    if !exists || blocks::is_blocked(&mut conn, current_user_id, user_id).await.map_err(internal_error)? {
+        return Err((StatusCode::NOT_FOUND, "User not found".to_string()));
+    }
+    drop(conn);
+
+    // Connected users already follow each other
+    if follows::source(&state.pool, current_user_id, user_id).await.map_err(internal_error)? == Some(FollowSource::Connection) {
+        return Ok((StatusCode::OK, Json(serde_json::json!({
+            "message": "You already follow this user through your connection",
+            "user_id": user_id,
+            "source": FollowSource::Connection
+        }))));
+    }
+
+    let created = follows::follow(&state.pool, current_user_id, user_id).await.map_err(internal_error)?;
+
+    let status = if created { StatusCode::CREATED } else { StatusCode::OK };
+    Ok((status, Json(serde_json::json!({
+        "message": "Following",
+        "user_id": user_id,
+        "source": FollowSource::Follow
+    }))))
+}
+
+pub async fn unfollow_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    follows::unfollow(&state.pool, current_user_id, user_id).await.map_err(internal_error)?;
+
+    match follows::source(&state.pool, current_user_id, user_id).await.map_err(internal_error)? {
+        Some(FollowSource::Connection) => Err((
+            StatusCode::CONFLICT,
+            "Connections always follow each other; remove the connection to stop following".to_string(),
+        )),
+        _ => Ok(StatusCode::NO_CONTENT),
+    }
+}
+
+pub async fn list_my_followers(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    followers_of(&state, current_user_id, current_user_id, &page).await.map(Json)
+}
+
+pub async fn list_my_following(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    following_of(&state, current_user_id, current_user_id, &page).await.map(Json)
+}
+
+pub async fn list_user_followers(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    require_visible_lists(&state, current_user_id, user_id).await?;
+
+    followers_of(&state, current_user_id, user_id, &page).await.map(Json)
+}
+
+pub async fn list_user_following(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    require_visible_lists(&state, current_user_id, user_id).await?;
+
+    following_of(&state, current_user_id, user_id, &page).await.map(Json)
+}
+
+/// Turns creator mode on or off. A creator's profile leads with Follow
+/// rather than Connect, and shows their follower count.
+pub async fn set_creator_mode(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreatorModeRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    sqlx::query("UPDATE users SET creator_mode = $2, updated_at = NOW() WHERE id = $1")
+        .bind(current_user_id)
+        .bind(payload.enabled)
+        .execute(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({ "creator_mode": payload.enabled })))
+}
@@ -0,0 +1,147 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+
+use crate::handles::{self, Resolved};
+use crate::models::UpdateHandleRequest;
+use crate::routes::{internal_error, profile, require_user, AppState};
+
+#[derive(Debug, Deserialize)]
+pub struct AvailabilityQuery {
+    pub handle: String,
+}
+
+pub async fn check_availability(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<AvailabilityQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let (available, reason) = match handles::validate(&query.handle) {
+        Err(e) => (false, Some(e.to_string())),
+        Ok(handle) => {
+            let mut conn = state.pool.acquire().await.map_err(internal_error)?;
+            let free = handles::is_available(&mut conn, &handle, Some(current_user_id))
+                .await
+                .map_err(internal_error)?;
+            (free, (!free).then(|| "Handle is already taken".to_string()))
+        }
+    };
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "handle": query.handle.trim().to_lowercase(),
+        "available": available,
+        "reason": reason
+    })))
+}
+
+pub async fn update_handle(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateHandleRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let handle = handles::validate(&payload.handle)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let (current_handle, changed_at): (String, Option<chrono::DateTime<Utc>>) = sqlx::query_as(
+        "SELECT handle, handle_changed_at FROM users WHERE id = $1 FOR UPDATE"
+    )
+        .bind(current_user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    if current_handle == handle {
+        return Ok(Json(serde_json::json!({ "handle": current_handle })));
+    }
+
+    if let Some(changed_at) = changed_at {
+        let next_change = changed_at + Duration::days(handles::CHANGE_COOLDOWN_DAYS);
+        if next_change > Utc::now() {
+            return Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                format!("Handle can be changed again after {}", next_change.format("%Y-%m-%d")),
+            ));
+        }
+    }
+
+    if !handles::is_available(&mut tx, &handle, Some(current_user_id)).await.map_err(internal_error)? {
+        return Err((StatusCode::CONFLICT, "Handle is already taken".to_string()));
+    }
+
+    sqlx::query(
+        "INSERT INTO handle_history (handle, user_id, expires_at)
+         VALUES ($1, $2, NOW() + make_interval(days => $3))
+         ON CONFLICT ((lower(handle))) DO UPDATE SET
+             user_id = EXCLUDED.user_id,
+             retired_at = NOW(),
+             expires_at = EXCLUDED.expires_at"
+    )
+        .bind(&current_handle)
+        .bind(current_user_id)
+        .bind(handles::REDIRECT_GRACE_DAYS as i32)
+        .execute(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    // Reclaiming one of your own old handles ends its redirect
+    sqlx::query("DELETE FROM handle_history WHERE lower(handle) = lower($1)")
+        .bind(&handle)
+        .execute(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    sqlx::query("UPDATE users SET handle = $1, handle_changed_at = NOW(), updated_at = NOW() WHERE id = $2")
+        .bind(&handle)
+        .bind(current_user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                (StatusCode::CONFLICT, "Handle is already taken".to_string())
+            }
+            e => internal_error(e),
+        })?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok(Json(serde_json::json!({ "handle": handle })))
+}
+
+/// Public profile page data at `/in/:handle`, always shown as an anonymous
+/// visitor would see it. Retired handles redirect to the owner's current one.
+pub async fn get_public_profile(
+    State(state): State<AppState>,
+    Path(handle): Path<String>,
+) -> Result<Response, (StatusCode, String)> {
+    let not_found = (StatusCode::NOT_FOUND, "Profile not found".to_string());
+
+    let mut conn = state.pool.acquire().await.map_err(internal_error)?;
+    let resolved = handles::resolve(&mut conn, &handle).await.map_err(internal_error)?;
+    drop(conn);
+
+    let user_id = match resolved {
+        None => return Err(not_found),
+        Some(Resolved::Redirect(current)) => {
+            return Ok(Redirect::temporary(&format!("/in/{}", current)).into_response());
+        }
+        Some(Resolved::Current(user_id)) => user_id,
+    };
+
+    let details = profile::load_profile_details(&state.pool, None, user_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or(not_found)?;
+
+    Ok(Json(details).into_response())
+}
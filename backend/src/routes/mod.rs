@@ -1,19 +1,50 @@
 pub mod auth;
+pub mod blocks;
 pub mod profile;
 pub mod connections;
+pub mod contacts;
+pub mod credentials;
+pub mod experience;
+pub mod follows;
+pub mod handles;
+pub mod pictures;
+pub mod privacy;
+pub mod profile_history;
+pub mod profile_views;
+pub mod recommendations;
+pub mod resume;
+pub mod sharing;
+pub mod skills;
+pub mod suggestions;
+pub mod trust;
 
 use axum::{
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Router,
 };
+use axum::extract::DefaultBodyLimit;
+use axum::http::{HeaderMap, StatusCode};
 use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
 use crate::auth::service::AuthService;
-use crate::auth::middleware::auth_middleware;
+use crate::auth::middleware::{auth_middleware, get_user_id_from_headers};
+use crate::credentials::CredentialIssuer;
+use crate::media::MAX_UPLOAD_BYTES;
+use crate::pagination::{Cursors, InvalidCursor};
+use crate::request_policy::RequestPolicy;
+use crate::storage::{self, Storage};
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
     pub auth_service: AuthService,
+    pub issuer: CredentialIssuer,
+    pub storage: Arc<dyn Storage>,
+    /// Where clients reach this API, for absolute links in exported documents.
+    pub public_base_url: String,
+    pub request_policy: RequestPolicy,
+    pub cursors: Cursors,
 }
 
 pub fn create_routes(state: AppState) -> Router {
@@ -21,9 +52,21 @@ pub fn create_routes(state: AppState) -> Router {
         .route("/", get(root))
         .route("/health", get(health_check))
         .route("/api/status", get(api_status))
+        .route("/.well-known/did.json", get(credentials::did_document))
+        .nest_service("/media", tower_http::services::ServeDir::new(storage::local_media_root()))
+        .route("/in/:handle", get(handles::get_public_profile))
+        .route("/connect/:token", get(sharing::open_share_link))
         .nest("/api/auth", auth_routes())
         .nest("/api/profile", profile_routes())
         .nest("/api/connections", connection_routes())
+        .nest("/api/credentials", credential_routes())
+        .nest("/api/trust", trust_routes())
+        .nest("/api/recommendations", recommendation_routes())
+        .nest("/api/moderation", moderation_routes())
+        .nest("/api/blocks", block_routes())
+        .nest("/api/mutes", mute_routes())
+        .route("/api/organizations", get(experience::search_organizations))
+        .route("/api/skills", get(skills::search_skills))
         .with_state(state)
         .layer(axum::middleware::from_fn(auth_middleware))
         .layer(
@@ -34,6 +77,46 @@ pub fn create_routes(state: AppState) -> Router {
         )
 }
 
+impl AppState {
+    /// Makes a stored path such as an uploaded picture's /media URL absolute.
+    pub fn absolute_url(&self, url: &str) -> String {
+        if url.starts_with('/') {
+            format!("{}{}", self.public_base_url, url)
+        } else {
+            url.to_string()
+        }
+    }
+}
+
+pub fn require_user(headers: &HeaderMap) -> Result<Uuid, (StatusCode, String)> {
+    get_user_id_from_headers(headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))
+}
+
+pub async fn require_moderator(pool: &sqlx::PgPool, headers: &HeaderMap) -> Result<Uuid, (StatusCode, String)> {
+    let current_user_id = require_user(headers)?;
+
+    let is_moderator: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE id = $1 AND role = 'moderator')")
+        .bind(current_user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(internal_error)?;
+
+    if !is_moderator {
+        return Err((StatusCode::FORBIDDEN, "Moderator access required".to_string()));
+    }
+
+    Ok(current_user_id)
+}
+
+pub fn internal_error(e: sqlx::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+pub fn invalid_cursor(_: InvalidCursor) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, "Invalid cursor".to_string())
+}
+
 async fn root() -> &'static str {
     "TrueLink API"
 }
@@ -60,6 +143,49 @@ fn profile_routes() -> Router<AppState> {
     Router::new()
         .route("/me", get(profile::get_user_profile))
         .route("/me", put(profile::update_user_profile))
+        .route("/me/completeness", get(profile::get_profile_completeness))
+        .route("/me/handle", put(handles::update_handle))
+        .route("/me/privacy", get(privacy::get_privacy_settings).put(privacy::update_privacy_settings))
+        .route("/me/views", get(profile_views::list_viewers))
+        .route("/me/views/stats", get(profile_views::view_stats))
+        .route("/me/revisions", get(profile_history::list_my_revisions))
+        .route("/me/revisions/:version/revert", post(profile_history::revert_profile))
+        .route("/me/export", get(resume::export_profile))
+        .route("/me/import", post(resume::import_profile))
+        .route("/me/qr", get(sharing::get_my_qr))
+        .route("/me/followers", get(follows::list_my_followers))
+        .route("/me/following", get(follows::list_my_following))
+        .route("/me/creator-mode", put(follows::set_creator_mode))
+        .route("/handle-availability", get(handles::check_availability))
+        .route("/me/positions", get(experience::list_positions).post(experience::create_position))
+        .route("/me/positions/order", put(experience::reorder_positions))
+        .route("/me/positions/:id", put(experience::update_position).delete(experience::delete_position))
+        .route("/me/education", get(experience::list_education).post(experience::create_education))
+        .route("/me/education/order", put(experience::reorder_education))
+        .route("/me/education/:id", put(experience::update_education).delete(experience::delete_education))
+        .route(
+            "/me/picture",
+            post(pictures::upload_profile_picture)
+                .delete(pictures::delete_profile_picture)
+                // Headroom over the image limit for multipart framing
+                .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES + 64 * 1024)),
+        )
+        .route("/me/skills", get(skills::list_my_skills).post(skills::add_skill))
+        .route("/me/skills/:skill_id", delete(skills::remove_skill))
+        .route("/:user_id", get(profile::get_profile_by_user_id))
+        .route("/:user_id/connections", get(connections::get_user_connections))
+        .route("/:user_id/follow", post(follows::follow_user).delete(follows::unfollow_user))
+        .route("/:user_id/followers", get(follows::list_user_followers))
+        .route("/:user_id/following", get(follows::list_user_following))
+        .route("/:user_id/vcard", get(sharing::get_vcard))
+        .route("/:user_id/skills", get(skills::list_user_skills))
+        .route("/:user_id/recommendations", get(recommendations::list_profile_recommendations))
+        .route(
+            "/:user_id/skills/:skill_id/endorsements",
+            get(skills::list_endorsements)
+                .post(skills::endorse_skill)
+                .delete(skills::remove_endorsement),
+        )
 }
 
 fn connection_routes() -> Router<AppState> {
@@ -67,6 +193,66 @@ fn connection_routes() -> Router<AppState> {
         .route("/search", get(connections::search_users))
         .route("/request", post(connections::send_connection_request))
         .route("/requests", get(connections::get_pending_requests))
-        .route("/requests/:id", put(connections::update_connection_request))
+        .route("/requests/sent", get(connections::get_sent_requests))
+        .route("/requests/bulk", post(connections::bulk_update_requests))
+        .route("/mutual/:user_id", get(connections::get_mutual_connections))
+        .route("/degree/:user_id", get(connections::get_connection_degree))
+        .route("/suggestions", get(suggestions::list_suggestions))
+        .route("/suggestions/:user_id/dismiss", post(suggestions::dismiss_suggestion))
+        .route(
+            "/requests/:id",
+            put(connections::update_connection_request).delete(connections::withdraw_connection_request),
+        )
+        .route("/tags", get(contacts::list_tag_counts))
         .route("/", get(connections::get_connections))
-}
\ No newline at end of file
+        .route("/:user_id", delete(connections::remove_connection))
+        .route("/:user_id/tags", put(contacts::set_connection_tags))
+        .route("/:user_id/tags/:tag", delete(contacts::remove_connection_tag))
+        .route("/:user_id/note", put(contacts::set_private_note))
+}
+
+fn credential_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(credentials::list_credentials))
+        .route("/tier", post(credentials::issue_tier_credential))
+        .route("/verify", post(credentials::verify_credential))
+        .route("/status/:list_id", get(credentials::get_status_list))
+        .route("/:id", delete(credentials::revoke_credential))
+}
+
+fn trust_routes() -> Router<AppState> {
+    Router::new()
+        .route("/:user_id", get(trust::get_trust_score))
+        .route("/vouches/:user_id", post(trust::vouch_for_user))
+        .route("/vouches/:user_id", delete(trust::withdraw_vouch))
+}
+
+fn recommendation_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", post(recommendations::write_recommendation))
+        .route("/received", get(recommendations::list_received))
+        .route("/given", get(recommendations::list_given))
+        .route("/requests", get(recommendations::list_requests).post(recommendations::request_recommendation))
+        .route("/:id", put(recommendations::edit_recommendation).delete(recommendations::delete_recommendation))
+        .route("/:id/approve", post(recommendations::approve_recommendation))
+        .route("/:id/hide", post(recommendations::hide_recommendation))
+        .route("/:id/revisions", get(recommendations::list_revisions))
+}
+
+fn block_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(blocks::list_blocked))
+        .route("/:user_id", post(blocks::block_user).delete(blocks::unblock_user))
+}
+
+fn mute_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(blocks::list_muted))
+        .route("/:user_id", post(blocks::mute_user).delete(blocks::unmute_user))
+}
+
+fn moderation_routes() -> Router<AppState> {
+    Router::new()
+        .route("/users/:user_id/profile-revisions", get(profile_history::list_user_revisions))
+        .route("/skills/:skill_id/merge", post(skills::merge_skill))
+}
@@ -0,0 +1,164 @@
+use axum::{
+    extract::{Multipart, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::media::{self, MAX_UPLOAD_BYTES};
+use crate::routes::{internal_error, require_user, AppState};
+
+fn thumbnail_key(user_id: Uuid, upload_id: Uuid, size: u32) -> String {
+    format!("avatars/{}/{}/{}.jpg", user_id, upload_id, size)
+}
+
+/// Best effort: a failed delete leaves an orphaned file, not a broken profile.
+async fn delete_thumbnails(state: &AppState, user_id: Uuid, upload_id: Uuid, sizes: &[i32]) {
+    for &size in sizes {
+        let key = thumbnail_key(user_id, upload_id, size as u32);
+        if let Err(e) = state.storage.delete(&key).await {
+            eprintln!("Failed to delete {}: {}", key, e);
+        }
+    }
+}
+
+/// Reads the `file` field, enforcing the size limit while streaming.
+async fn read_upload(mut multipart: Multipart) -> Result<Vec<u8>, (StatusCode, String)> {
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (e.status(), e.body_text()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|e| (e.status(), e.body_text()))?
+        {
+            if bytes.len() + chunk.len() > MAX_UPLOAD_BYTES {
+                return Err((
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    format!("Image must be at most {} MB", MAX_UPLOAD_BYTES / (1024 * 1024)),
+                ));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        return Ok(bytes);
+    }
+
+    Err((StatusCode::BAD_REQUEST, "Multipart field 'file' is required".to_string()))
+}
+
+pub async fn upload_profile_picture(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let bytes = read_upload(multipart).await?;
+
+    if media::sniff_format(&bytes).is_none() {
+        return Err((StatusCode::UNSUPPORTED_MEDIA_TYPE, media::MediaError::UnsupportedFormat.to_string()));
+    }
+
+    let thumbnails = tokio::task::spawn_blocking(move || media::process_profile_picture(&bytes))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    let upload_id = Uuid::new_v4();
+    let mut urls = BTreeMap::new();
+    let sizes: Vec<i32> = thumbnails.iter().map(|(size, _)| *size as i32).collect();
+
+    for (size, jpeg) in thumbnails {
+        let key = thumbnail_key(current_user_id, upload_id, size);
+        if let Err(e) = state.storage.put(&key, jpeg, "image/jpeg").await {
+            delete_thumbnails(&state, current_user_id, upload_id, &sizes).await;
+            return Err((StatusCode::BAD_GATEWAY, e.to_string()));
+        }
+        urls.insert(size.to_string(), state.storage.public_url(&key));
+    }
+
+    let picture_url = state.storage.public_url(&thumbnail_key(current_user_id, upload_id, media::THUMBNAIL_SIZES[0]));
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let previous: Option<(Uuid, Vec<i32>)> = sqlx::query_as(
+        "SELECT upload_id, sizes FROM profile_pictures WHERE user_id = $1 FOR UPDATE"
+    )
+        .bind(current_user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    sqlx::query(
+        "INSERT INTO profile_pictures (user_id, upload_id, sizes) VALUES ($1, $2, $3)
+         ON CONFLICT (user_id) DO UPDATE SET
+             upload_id = EXCLUDED.upload_id, sizes = EXCLUDED.sizes, created_at = NOW()"
+    )
+        .bind(current_user_id)
+        .bind(upload_id)
+        .bind(&sizes)
+        .execute(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    sqlx::query("UPDATE users SET profile_picture_url = $1, updated_at = NOW() WHERE id = $2")
+        .bind(&picture_url)
+        .bind(current_user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    if let Some((previous_upload_id, previous_sizes)) = previous {
+        delete_thumbnails(&state, current_user_id, previous_upload_id, &previous_sizes).await;
+    }
+
+    Ok((StatusCode::CREATED, Json(serde_json::json!({
+        "profile_picture_url": picture_url,
+        "thumbnails": urls
+    }))))
+}
+
+pub async fn delete_profile_picture(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let previous: Option<(Uuid, Vec<i32>)> = sqlx::query_as(
+        "DELETE FROM profile_pictures WHERE user_id = $1 RETURNING upload_id, sizes"
+    )
+        .bind(current_user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    let Some((upload_id, sizes)) = previous else {
+        return Err((StatusCode::NOT_FOUND, "No uploaded profile picture".to_string()));
+    };
+
+    sqlx::query("UPDATE users SET profile_picture_url = NULL, updated_at = NOW() WHERE id = $1")
+        .bind(current_user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    delete_thumbnails(&state, current_user_id, upload_id, &sizes).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
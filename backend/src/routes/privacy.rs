@@ -0,0 +1,58 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+
+use crate::models::{PrivacySettings, UpdatePrivacySettingsRequest};
+use crate::routes::{internal_error, require_user, AppState};
+
+pub async fn get_privacy_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let settings: Option<PrivacySettings> = sqlx::query_as("SELECT * FROM privacy_settings WHERE user_id = $1")
+        .bind(current_user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(
+        settings.unwrap_or_else(|| PrivacySettings::defaults(current_user_id)),
+    ))
+}
+
+pub async fn update_privacy_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdatePrivacySettingsRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let settings: PrivacySettings = sqlx::query_as(
+        "INSERT INTO privacy_settings (user_id, email, profile_fields, connection_list, last_name, browse_privately)
+         VALUES ($1, COALESCE($2, 'connections'), COALESCE($3, 'public'), COALESCE($4, 'connections'),
+                 COALESCE($5, 'public'), COALESCE($6, FALSE))
+         ON CONFLICT (user_id) DO UPDATE SET
+             email = COALESCE($2, privacy_settings.email),
+             profile_fields = COALESCE($3, privacy_settings.profile_fields),
+             connection_list = COALESCE($4, privacy_settings.connection_list),
+             last_name = COALESCE($5, privacy_settings.last_name),
+             browse_privately = COALESCE($6, privacy_settings.browse_privately)
+         RETURNING *"
+    )
+        .bind(current_user_id)
+        .bind(payload.email)
+        .bind(payload.profile_fields)
+        .bind(payload.connection_list)
+        .bind(payload.last_name)
+        .bind(payload.browse_privately)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(settings))
+}
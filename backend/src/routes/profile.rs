@@ -1,38 +1,225 @@
 use axum::{
-    extract::{State, Json},
-    http::StatusCode,
+    extract::{Path, State, Json},
+    http::{StatusCode, HeaderMap},
     response::IntoResponse,
 };
-use crate::routes::AppState;
-use crate::models::{UserProfile, UpdateProfileRequest};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::get_user_id_from_headers;
+use crate::models::{ProfileDetails, ProfileView, UpdateProfileRequest, UserProfile};
+use crate::completeness;
+use crate::follows;
+use crate::privacy::{self, Field, ViewerAccess};
+use crate::profile_history::{self, ProfileFields};
+use crate::profile_views;
+use crate::routes::{experience, AppState};
+
+/// Profiles are created lazily, so every user has one to read and update.
+async fn ensure_profile(pool: &sqlx::PgPool, user_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO user_profiles (user_id) VALUES ($1) ON CONFLICT (user_id) DO NOTHING")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Whether `viewer_id` may see `owner_id`'s profile fields and what hangs
+/// off them, such as skills and recommendations. Unknown users are simply
+/// not viewable.
+pub async fn can_view_profile(
+    pool: &sqlx::PgPool,
+    viewer_id: Option<Uuid>,
+    owner_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE id = $1)")
+        .bind(owner_id)
+        .fetch_one(pool)
+        .await?;
+
+    if !exists {
+        return Ok(false);
+    }
+
+    let access = ViewerAccess::load(pool, viewer_id, &[owner_id]).await?;
+    Ok(access.can_see(owner_id, Field::ProfileFields))
+}
 
 pub async fn get_user_profile(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    let profile = UserProfile {
-        id: 1,
-        user_id: 1,
-        headline: Some("Software Engineer".to_string()),
-        summary: Some("Passionate about building great products.".to_string()),
-        location: Some("San Francisco, CA".to_string()),
-        website: Some("https://example.com".to_string()),
-        created_at: chrono::Utc::now(),
-        updated_at: chrono::Utc::now(),
-    };
+    let current_user_id = get_user_id_from_headers(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))?;
+
+    ensure_profile(&state.pool, current_user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    (StatusCode::OK, Json(profile))
+    let profile: UserProfile = sqlx::query_as("SELECT * FROM user_profiles WHERE user_id = $1")
+        .bind(current_user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok::<_, (StatusCode, String)>((StatusCode::OK, Json(profile)))
 }
 
 pub async fn update_user_profile(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
     Json(profile_data): Json<UpdateProfileRequest>,
 ) -> impl IntoResponse {
-    println!("Updating profile with: {:?}", profile_data);
+    let current_user_id = get_user_id_from_headers(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))?;
+
+    let profile_data = UpdateProfileRequest {
+        headline: non_empty(profile_data.headline),
+        summary: non_empty(profile_data.summary),
+        location: non_empty(profile_data.location),
+        website: non_empty(profile_data.website),
+    };
+
+    if let Err(validation_errors) = profile_data.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+
+    let fields = ProfileFields {
+        headline: profile_data.headline,
+        summary: profile_data.summary,
+        location: profile_data.location,
+        website: profile_data.website,
+    };
+
+    let mut tx = state.pool.begin().await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let revision = profile_history::save(&mut tx, current_user_id, current_user_id, &fields, None)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let profile: UserProfile = sqlx::query_as("SELECT * FROM user_profiles WHERE user_id = $1")
+        .bind(current_user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    tx.commit().await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     let response = serde_json::json!({
         "message": "Profile updated successfully",
-        "profile": profile_data
+        "profile": profile,
+        "revision": revision.map(|revision| revision.version)
     });
 
-    (StatusCode::OK, Json(response))
-}
\ No newline at end of file
+    Ok((StatusCode::OK, Json(response)))
+}
+
+#[derive(sqlx::FromRow)]
+struct ProfileExtras {
+    verification_tier: Option<String>,
+    summary: Option<String>,
+    location: Option<String>,
+    website: Option<String>,
+    creator_mode: bool,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A user's full profile as `viewer_id` may see it, or `None` for unknown users.
+pub async fn load_profile_details(
+    pool: &sqlx::PgPool,
+    viewer_id: Option<Uuid>,
+    user_id: Uuid,
+) -> Result<Option<ProfileDetails>, sqlx::Error> {
+    let Some(user) = privacy::user_cards(pool, viewer_id, &[user_id]).await?.remove(&user_id) else {
+        return Ok(None);
+    };
+
+    let fields: ProfileExtras = sqlx::query_as(
+        "SELECT u.verification_tier, p.summary, p.location, p.website, u.creator_mode,
+                COALESCE(p.updated_at, u.updated_at, NOW()) AS updated_at
+         FROM users u
+         LEFT JOIN user_profiles p ON p.user_id = u.id
+         WHERE u.id = $1"
+    )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+    let access = ViewerAccess::load(pool, viewer_id, &[user_id]).await?;
+
+    let (fields, positions, education) = if access.can_see(user_id, Field::ProfileFields) {
+        let positions = experience::list_user_positions(pool, user_id).await?;
+        let education = experience::list_user_education(pool, user_id).await?;
+        (fields, positions, education)
+    } else {
+        let hidden = ProfileExtras { summary: None, location: None, website: None, ..fields };
+        (hidden, Vec::new(), Vec::new())
+    };
+
+    let follow_counts = if access.can_see(user_id, Field::ConnectionList) {
+        Some(follows::counts(pool, user_id).await?)
+    } else {
+        None
+    };
+
+    let profile = ProfileView {
+        user,
+        verification_tier: fields.verification_tier,
+        summary: fields.summary,
+        location: fields.location,
+        website: fields.website,
+        creator_mode: fields.creator_mode,
+        follow_counts,
+        updated_at: fields.updated_at,
+    };
+
+    Ok(Some(ProfileDetails { profile, positions, education }))
+}
+
+/// Another user's profile.
+pub async fn get_profile_by_user_id(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let viewer_id = get_user_id_from_headers(&headers);
+
+    let details = load_profile_details(&state.pool, viewer_id, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Profile not found".to_string()))?;
+
+    // Analytics must never break the profile page
+    if let Some(viewer_id) = viewer_id
+        && let Err(e) = profile_views::record(&state.pool, viewer_id, user_id).await
+    {
+        eprintln!("Failed to record profile view of {}: {}", user_id, e);
+    }
+
+    Ok::<_, (StatusCode, String)>((StatusCode::OK, Json(details)))
+}
+
+/// The caller's completeness score and the onboarding steps still missing.
+pub async fn get_profile_completeness(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current_user_id = get_user_id_from_headers(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))?;
+
+    let completeness = completeness::checklist(&state.pool, current_user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    Ok::<_, (StatusCode, String)>(Json(completeness))
+}
@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::ProfileRevision;
+use crate::pagination::{PageQuery, TOTAL_ESTIMATE_CAP};
+use crate::profile_history::{self, ProfileFields};
+use crate::routes::{internal_error, invalid_cursor, require_moderator, require_user, AppState};
+
+#[derive(Debug, Deserialize)]
+pub struct RevisionQuery {
+    /// Only revisions that changed this field, e.g. `headline`.
+    pub field: Option<String>,
+}
+
+/// One page of a user's revisions, newest first, with a cursor for the next
+/// and an estimate of the total.
+async fn list_revisions(
+    state: &AppState,
+    user_id: Uuid,
+    field: Option<&str>,
+    page: &PageQuery,
+) -> Result<(Vec<ProfileRevision>, Option<String>, i64), (StatusCode, String)> {
+    let after: Option<i32> = state.cursors.decode("profile_revisions", page).map_err(invalid_cursor)?;
+
+    let revisions: Vec<ProfileRevision> = sqlx::query_as(
+        "SELECT * FROM profile_revisions
+         WHERE user_id = $1
+           AND ($2::varchar IS NULL OR changes @> jsonb_build_array(jsonb_build_object('field', $2::varchar)))
+           AND ($3::int IS NULL OR version < $3)
+         ORDER BY version DESC
+         LIMIT $4"
+    )
+        .bind(user_id)
+        .bind(field)
+        .bind(after)
+        .bind(page.fetch_limit())
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let total_estimate: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (
+             SELECT 1 FROM profile_revisions
+             WHERE user_id = $1
+               AND ($2::varchar IS NULL OR changes @> jsonb_build_array(jsonb_build_object('field', $2::varchar)))
+             LIMIT $3
+         ) revisions"
+    )
+        .bind(user_id)
+        .bind(field)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let (revisions, next_cursor) = state.cursors.page("profile_revisions", page, revisions, |revision| revision.version);
+    Ok((revisions, next_cursor, total_estimate))
+}
+
+/// The caller's profile history, newest first.
+pub async fn list_my_revisions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<RevisionQuery>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let (revisions, next_cursor, total_estimate) = list_revisions(&state, current_user_id, query.field.as_deref(), &page).await?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "revisions": revisions,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
+
+/// Restores the profile fields of an earlier revision. The revert is itself
+/// a new revision, so history is never rewritten.
+pub async fn revert_profile(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(version): Path<i32>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let target = profile_history::find(&mut tx, current_user_id, version)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, "Revision not found".to_string()))?;
+
+    let revision = profile_history::save(
+        &mut tx,
+        current_user_id,
+        current_user_id,
+        &ProfileFields::from(&target),
+        Some(target.version),
+    )
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::CONFLICT, "Profile already matches this revision".to_string()))?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(revision))
+}
+
+/// Any user's full profile history for moderators, regardless of privacy
+/// settings. Reverted values stay in the history, so abuse that was later
+/// edited away is still visible here.
+pub async fn list_user_revisions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<RevisionQuery>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let moderator_id = require_moderator(&state.pool, &headers).await?;
+
+    let (revisions, next_cursor, total_estimate) = list_revisions(&state, user_id, query.field.as_deref(), &page).await?;
+
+    println!("Moderator {} reviewed the profile history of {}", moderator_id, user_id);
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "user_id": user_id,
+        "revisions": revisions,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
@@ -0,0 +1,170 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::{ProfileViewer, StatsPeriod, ViewStat};
+use crate::pagination::{PageQuery, TOTAL_ESTIMATE_CAP};
+use crate::privacy;
+use crate::profile_views;
+use crate::routes::{internal_error, invalid_cursor, require_user, AppState};
+
+const MAX_STATS_DAYS: i32 = 365;
+
+#[derive(sqlx::FromRow)]
+struct ViewRow {
+    id: Uuid,
+    viewer_id: Uuid,
+    viewed_at: chrono::DateTime<chrono::Utc>,
+    anonymous: bool,
+    viewer_organization: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    pub period: Option<StatsPeriod>,
+    pub days: Option<i32>,
+}
+
+/// Recent viewers of the caller's profile, newest first, a page at a time.
+/// Only raw views are listed, so this covers the retention window.
+pub async fn list_viewers(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    let after: Option<(chrono::DateTime<chrono::Utc>, Uuid)> = state.cursors
+        .decode("profile_viewers", &page)
+        .map_err(invalid_cursor)?;
+
+    let views: Vec<ViewRow> = sqlx::query_as(
+        "SELECT id, viewer_id, viewed_at, anonymous, viewer_organization
+         FROM profile_views v
+         WHERE viewed_id = $1
+           AND NOT EXISTS (SELECT 1 FROM user_mutes m WHERE m.muter_id = $1 AND m.muted_id = v.viewer_id)
+           AND NOT EXISTS (
+               SELECT 1 FROM user_blocks b
+               WHERE (b.blocker_id = $1 AND b.blocked_id = v.viewer_id) OR (b.blocker_id = v.viewer_id AND b.blocked_id = $1)
+           )
+           AND ($2::timestamptz IS NULL OR (viewed_at, id) < ($2, $3))
+         ORDER BY viewed_at DESC, id DESC
+         LIMIT $4"
+    )
+        .bind(current_user_id)
+        .bind(after.map(|(viewed_at, _)| viewed_at))
+        .bind(after.map(|(_, id)| id))
+        .bind(page.fetch_limit())
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let total_estimate: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (
+             SELECT 1 FROM profile_views v
+             WHERE viewed_id = $1
+               AND NOT EXISTS (SELECT 1 FROM user_mutes m WHERE m.muter_id = $1 AND m.muted_id = v.viewer_id)
+               AND NOT EXISTS (
+                   SELECT 1 FROM user_blocks b
+                   WHERE (b.blocker_id = $1 AND b.blocked_id = v.viewer_id) OR (b.blocker_id = v.viewer_id AND b.blocked_id = $1)
+               )
+             LIMIT $2
+         ) viewers"
+    )
+        .bind(current_user_id)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let (views, next_cursor) = state.cursors.page("profile_viewers", &page, views, |view| (view.viewed_at, view.id));
+
+    // Anonymous viewers are never looked up, so nothing about them can leak
+    let named: Vec<Uuid> = views.iter().filter(|view| !view.anonymous).map(|view| view.viewer_id).collect();
+    let cards = privacy::user_cards(&state.pool, Some(current_user_id), &named)
+        .await
+        .map_err(internal_error)?;
+
+    let viewers: Vec<ProfileViewer> = views
+        .into_iter()
+        .filter_map(|view| {
+            if view.anonymous {
+                return Some(ProfileViewer {
+                    viewed_at: view.viewed_at,
+                    anonymous: true,
+                    viewer: None,
+                    description: Some(profile_views::anonymous_description(view.viewer_organization.as_deref())),
+                });
+            }
+
+            cards.get(&view.viewer_id).cloned().map(|card| ProfileViewer {
+                viewed_at: view.viewed_at,
+                anonymous: false,
+                viewer: Some(card),
+                description: None,
+            })
+        })
+        .collect();
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "viewers": viewers,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
+
+/// View counts per day or week, zero-filled, over the last `days` days.
+/// Combines raw views with the daily counts they were rolled up into.
+pub async fn view_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<StatsQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let period = query.period.unwrap_or(StatsPeriod::Day);
+    let days = query.days.unwrap_or(30).clamp(1, MAX_STATS_DAYS);
+
+    let series: Vec<ViewStat> = sqlx::query_as(
+        "WITH daily AS (
+             SELECT view_date AS day, COUNT(*) AS views, COUNT(*) FILTER (WHERE anonymous) AS anonymous_views
+             FROM profile_views
+             WHERE viewed_id = $1
+             GROUP BY view_date
+             UNION ALL
+             SELECT view_date, view_count, anonymous_count
+             FROM profile_view_daily
+             WHERE viewed_id = $1
+         )
+         SELECT s.period_start::date AS period_start,
+                COALESCE(SUM(d.views), 0)::bigint AS views,
+                COALESCE(SUM(d.anonymous_views), 0)::bigint AS anonymous_views
+         FROM generate_series(
+                  date_trunc($2, (CURRENT_DATE - $3)::timestamp),
+                  date_trunc($2, CURRENT_DATE::timestamp),
+                  ('1 ' || $2)::interval
+              ) AS s(period_start)
+         LEFT JOIN daily d ON date_trunc($2, d.day::timestamp) = s.period_start
+         GROUP BY s.period_start
+         ORDER BY s.period_start"
+    )
+        .bind(current_user_id)
+        .bind(period.as_str())
+        .bind(days - 1)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let total_views: i64 = series.iter().map(|stat| stat.views).sum();
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "period": period,
+        "days": days,
+        "total_views": total_views,
+        "series": series
+    })))
+}
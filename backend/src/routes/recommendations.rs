@@ -0,0 +1,490 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::get_user_id_from_headers;
+use crate::models::{
+    EditRecommendationRequest, Recommendation, RecommendationRevision, RecommendationStatus,
+    RequestRecommendationRequest, UserCard, WriteRecommendationRequest,
+};
+use crate::pagination::{PageQuery, TOTAL_ESTIMATE_CAP};
+use crate::privacy;
+use crate::relationships;
+use crate::routes::{internal_error, invalid_cursor, profile, require_user, AppState};
+
+#[derive(FromRow)]
+struct RecommendationRow {
+    #[sqlx(flatten)]
+    recommendation: Recommendation,
+    counterpart_id: Uuid,
+}
+
+/// A recommendation with the other party's display details.
+#[derive(Debug, Serialize)]
+pub struct RecommendationWithUser {
+    #[serde(flatten)]
+    pub recommendation: Recommendation,
+    pub counterpart: UserCard,
+}
+
+async fn require_connection(state: &AppState, a: Uuid, b: Uuid) -> Result<(), (StatusCode, String)> {
+    let connected = relationships::are_connected(&state.pool, a, b)
+        .await
+        .map_err(internal_error)?;
+
+    if !connected {
+        return Err((StatusCode::FORBIDDEN, "Recommendations are only allowed between connections".to_string()));
+    }
+
+    Ok(())
+}
+
+async fn fetch_recommendation(state: &AppState, id: Uuid) -> Result<Recommendation, (StatusCode, String)> {
+    sqlx::query_as("SELECT * FROM recommendations WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, "Recommendation not found".to_string()))
+}
+
+enum Listing {
+    Received,
+    Given,
+    OpenRequests,
+    Profile,
+}
+
+impl Listing {
+    fn scope(&self) -> &'static str {
+        match self {
+            Listing::Received => "recommendations_received",
+            Listing::Given => "recommendations_given",
+            Listing::OpenRequests => "recommendation_requests",
+            Listing::Profile => "profile_recommendations",
+        }
+    }
+}
+
+/// One page of a listing, most recently updated first, with a cursor for the next.
+async fn list_with_counterpart(
+    state: &AppState,
+    viewer_id: Option<Uuid>,
+    listing: Listing,
+    user_id: Uuid,
+    page: &PageQuery,
+) -> Result<(Vec<RecommendationWithUser>, Option<String>, i64), (StatusCode, String)> {
+    let after: Option<(chrono::DateTime<chrono::Utc>, Uuid)> = state.cursors
+        .decode(listing.scope(), page)
+        .map_err(invalid_cursor)?;
+
+    // The listing picks which side `user_id` is on; the counterpart is the other side
+    let (counterpart_column, condition) = match listing {
+        Listing::Received => ("author_id", "r.recipient_id = $1 AND r.status <> 'requested'"),
+        Listing::Given => ("recipient_id", "r.author_id = $1 AND r.status <> 'requested'"),
+        Listing::OpenRequests => (
+            "recipient_id",
+            "r.author_id = $1 AND r.status = 'requested'
+             AND NOT EXISTS (SELECT 1 FROM user_mutes m WHERE m.muter_id = $1 AND m.muted_id = r.recipient_id)",
+        ),
+        Listing::Profile => ("author_id", "r.recipient_id = $1 AND r.status = 'visible'"),
+    };
+
+    // Counterparts blocked either way with the viewer are left out of the
+    // page and the total alike
+    let listed = format!(
+        "{condition}
+           AND NOT EXISTS (
+                   SELECT 1 FROM user_blocks b
+                   WHERE (b.blocker_id = $2 AND b.blocked_id = r.{counterpart})
+                      OR (b.blocker_id = r.{counterpart} AND b.blocked_id = $2)
+               )",
+        condition = condition, counterpart = counterpart_column
+    );
+
+    let rows: Vec<RecommendationRow> = sqlx::query_as(&format!(
+        "SELECT r.*, r.{} AS counterpart_id
+         FROM recommendations r
+         WHERE {}
+           AND ($3::timestamptz IS NULL OR (r.updated_at, r.id) < ($3, $4))
+         ORDER BY r.updated_at DESC, r.id DESC
+         LIMIT $5",
+        counterpart_column, listed
+    ))
+        .bind(user_id)
+        .bind(viewer_id)
+        .bind(after.map(|(updated_at, _)| updated_at))
+        .bind(after.map(|(_, id)| id))
+        .bind(page.fetch_limit())
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let total_estimate: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM (SELECT 1 FROM recommendations r WHERE {} LIMIT $3) listed",
+        listed
+    ))
+        .bind(user_id)
+        .bind(viewer_id)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let (rows, next_cursor) = state.cursors.page(listing.scope(), page, rows, |row| {
+        (row.recommendation.updated_at, row.recommendation.id)
+    });
+
+    let rows = privacy::attach_cards(&state.pool, viewer_id, rows, |row| row.counterpart_id)
+        .await
+        .map_err(internal_error)?;
+
+    let recommendations = rows
+        .into_iter()
+        .map(|(row, counterpart)| RecommendationWithUser { recommendation: row.recommendation, counterpart })
+        .collect();
+
+    Ok((recommendations, next_cursor, total_estimate))
+}
+
+/// The recipient asks a connection to write them a recommendation.
+pub async fn request_recommendation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<RequestRecommendationRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+    if payload.author_id == current_user_id {
+        return Err((StatusCode::BAD_REQUEST, "Cannot request a recommendation from yourself".to_string()));
+    }
+
+    require_connection(&state, current_user_id, payload.author_id).await?;
+
+    let recommendation: Recommendation = sqlx::query_as(
+        "INSERT INTO recommendations (author_id, recipient_id, status, request_message)
+         VALUES ($1, $2, 'requested', $3)
+         ON CONFLICT (author_id, recipient_id) DO NOTHING
+         RETURNING *"
+    )
+        .bind(payload.author_id)
+        .bind(current_user_id)
+        .bind(&payload.message)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::CONFLICT, "A recommendation from this person already exists".to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(recommendation)))
+}
+
+/// The author writes a recommendation, fulfilling an open request if there is one.
+/// It stays invisible until the recipient approves it.
+pub async fn write_recommendation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<WriteRecommendationRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+    if payload.recipient_id == current_user_id {
+        return Err((StatusCode::BAD_REQUEST, "Cannot recommend yourself".to_string()));
+    }
+
+    require_connection(&state, current_user_id, payload.recipient_id).await?;
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let recommendation: Recommendation = sqlx::query_as(
+        "INSERT INTO recommendations (author_id, recipient_id, status, relationship, body, version)
+         VALUES ($1, $2, 'pending_approval', $3, $4, 1)
+         ON CONFLICT (author_id, recipient_id) DO UPDATE SET
+             status = 'pending_approval',
+             relationship = EXCLUDED.relationship,
+             body = EXCLUDED.body,
+             version = 1
+         WHERE recommendations.status = 'requested'
+         RETURNING *"
+    )
+        .bind(current_user_id)
+        .bind(payload.recipient_id)
+        .bind(payload.relationship)
+        .bind(payload.body.trim())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::CONFLICT, "You already wrote a recommendation for this person; edit it instead".to_string()))?;
+
+    sqlx::query(
+        "INSERT INTO recommendation_revisions (recommendation_id, version, relationship, body)
+         VALUES ($1, $2, $3, $4)"
+    )
+        .bind(recommendation.id)
+        .bind(recommendation.version)
+        .bind(payload.relationship)
+        .bind(payload.body.trim())
+        .execute(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok((StatusCode::CREATED, Json(recommendation)))
+}
+
+/// Edits create a new version and send the recommendation back for approval.
+pub async fn edit_recommendation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recommendation_id): Path<Uuid>,
+    Json(payload): Json<EditRecommendationRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+
+    let existing = fetch_recommendation(&state, recommendation_id).await?;
+    if existing.author_id != current_user_id {
+        return Err((StatusCode::NOT_FOUND, "Recommendation not found".to_string()));
+    }
+    if existing.status == RecommendationStatus::Requested {
+        return Err((StatusCode::CONFLICT, "Write the recommendation before editing it".to_string()));
+    }
+
+    require_connection(&state, current_user_id, existing.recipient_id).await?;
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let recommendation: Recommendation = sqlx::query_as(
+        "UPDATE recommendations
+         SET relationship = $2, body = $3, version = version + 1, status = 'pending_approval'
+         WHERE id = $1
+         RETURNING *"
+    )
+        .bind(recommendation_id)
+        .bind(payload.relationship)
+        .bind(payload.body.trim())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    sqlx::query(
+        "INSERT INTO recommendation_revisions (recommendation_id, version, relationship, body)
+         VALUES ($1, $2, $3, $4)"
+    )
+        .bind(recommendation.id)
+        .bind(recommendation.version)
+        .bind(payload.relationship)
+        .bind(payload.body.trim())
+        .execute(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok(Json(recommendation))
+}
+
+async fn set_status_as_recipient(
+    state: &AppState,
+    headers: &HeaderMap,
+    recommendation_id: Uuid,
+    status: RecommendationStatus,
+) -> Result<Json<Recommendation>, (StatusCode, String)> {
+    let current_user_id = require_user(headers)?;
+
+    let existing = fetch_recommendation(state, recommendation_id).await?;
+    if existing.recipient_id != current_user_id {
+        return Err((StatusCode::NOT_FOUND, "Recommendation not found".to_string()));
+    }
+    if existing.status == RecommendationStatus::Requested {
+        return Err((StatusCode::CONFLICT, "This recommendation hasn't been written yet".to_string()));
+    }
+
+    let recommendation: Recommendation = sqlx::query_as(
+        "UPDATE recommendations SET status = $2 WHERE id = $1 RETURNING *"
+    )
+        .bind(recommendation_id)
+        .bind(status)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(recommendation))
+}
+
+pub async fn approve_recommendation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recommendation_id): Path<Uuid>,
+) -> impl IntoResponse {
+    set_status_as_recipient(&state, &headers, recommendation_id, RecommendationStatus::Visible).await
+}
+
+pub async fn hide_recommendation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recommendation_id): Path<Uuid>,
+) -> impl IntoResponse {
+    set_status_as_recipient(&state, &headers, recommendation_id, RecommendationStatus::Hidden).await
+}
+
+/// Either party may delete: the recipient to drop it, the author to withdraw
+/// it or decline a request.
+pub async fn delete_recommendation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recommendation_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let result = sqlx::query(
+        "DELETE FROM recommendations WHERE id = $1 AND (author_id = $2 OR recipient_id = $2)"
+    )
+        .bind(recommendation_id)
+        .bind(current_user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Recommendation not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_revisions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(recommendation_id): Path<Uuid>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let existing = fetch_recommendation(&state, recommendation_id).await?;
+    if existing.author_id != current_user_id && existing.recipient_id != current_user_id {
+        return Err((StatusCode::NOT_FOUND, "Recommendation not found".to_string()));
+    }
+
+    let after: Option<i32> = state.cursors.decode("recommendation_revisions", &page).map_err(invalid_cursor)?;
+
+    let revisions: Vec<RecommendationRevision> = sqlx::query_as(
+        "SELECT * FROM recommendation_revisions
+         WHERE recommendation_id = $1 AND ($2::int IS NULL OR version > $2)
+         ORDER BY version
+         LIMIT $3"
+    )
+        .bind(recommendation_id)
+        .bind(after)
+        .bind(page.fetch_limit())
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let total_estimate: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (SELECT 1 FROM recommendation_revisions WHERE recommendation_id = $1 LIMIT $2) revisions"
+    )
+        .bind(recommendation_id)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let (revisions, next_cursor) = state.cursors.page("recommendation_revisions", &page, revisions, |revision| revision.version);
+
+    Ok(Json(serde_json::json!({
+        "revisions": revisions,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
+
+pub async fn list_received(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    let (recommendations, next_cursor, total_estimate) =
+        list_with_counterpart(&state, Some(current_user_id), Listing::Received, current_user_id, &page).await?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "recommendations": recommendations,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
+
+pub async fn list_given(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    let (recommendations, next_cursor, total_estimate) =
+        list_with_counterpart(&state, Some(current_user_id), Listing::Given, current_user_id, &page).await?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "recommendations": recommendations,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
+
+/// Requests waiting for the caller to write a recommendation.
+pub async fn list_requests(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    let (requests, next_cursor, total_estimate) =
+        list_with_counterpart(&state, Some(current_user_id), Listing::OpenRequests, current_user_id, &page).await?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "requests": requests,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
+
+/// Approved recommendations shown on a profile.
+pub async fn list_profile_recommendations(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let viewer_id = get_user_id_from_headers(&headers);
+
+    let visible = profile::can_view_profile(&state.pool, viewer_id, user_id)
+        .await
+        .map_err(internal_error)?;
+    if !visible {
+        return Err((StatusCode::NOT_FOUND, "Profile not found".to_string()));
+    }
+
+    let (recommendations, next_cursor, total_estimate) = list_with_counterpart(&state, viewer_id, Listing::Profile, user_id, &page).await?;
+
+    Ok(Json(serde_json::json!({
+        "recommendations": recommendations,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
@@ -0,0 +1,301 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::models::{EducationRequest, PositionRequest, UserProfile};
+use crate::organizations;
+use crate::profile_history;
+use crate::resume::{self, Basics, JsonResume, Location, Meta, ResumeSkill, SocialProfile};
+use crate::routes::{experience, internal_error, require_user, AppState};
+use crate::skills;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Defaults to true, so nothing changes until the caller has seen the diff.
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub current: Option<String>,
+    pub imported: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntryDiff<T> {
+    pub add: Vec<T>,
+    /// Entries already on the profile, which are left alone.
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportDiff {
+    pub applied: bool,
+    pub profile: Vec<FieldChange>,
+    pub positions: EntryDiff<PositionRequest>,
+    pub education: EntryDiff<EducationRequest>,
+    pub skills: EntryDiff<String>,
+    /// Entries that couldn't be imported, with the reason.
+    pub skipped: Vec<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ExportUser {
+    full_name: String,
+    email: String,
+    handle: String,
+    profile_picture_url: Option<String>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+async fn load_profile(pool: &sqlx::PgPool, user_id: Uuid) -> Result<Option<UserProfile>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM user_profiles WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The caller's profile as a JSON Resume document.
+pub async fn export_profile(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ExportQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let format = query.format.as_deref().unwrap_or("jsonresume");
+    if format != "jsonresume" {
+        return Err((StatusCode::BAD_REQUEST, format!("Unsupported export format: {}", format)));
+    }
+
+    let user: ExportUser = sqlx::query_as(
+        "SELECT u.full_name, u.email, u.handle, u.profile_picture_url,
+                GREATEST(u.updated_at, COALESCE(p.updated_at, u.updated_at)) AS updated_at
+         FROM users u
+         LEFT JOIN user_profiles p ON p.user_id = u.id
+         WHERE u.id = $1"
+    )
+        .bind(current_user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    let profile = load_profile(&state.pool, current_user_id).await.map_err(internal_error)?;
+    let positions = experience::list_user_positions(&state.pool, current_user_id).await.map_err(internal_error)?;
+    let education = experience::list_user_education(&state.pool, current_user_id).await.map_err(internal_error)?;
+    let ranked = skills::ranked_skills(&state.pool, current_user_id).await.map_err(internal_error)?;
+
+    let profile_url = format!("{}/in/{}", state.public_base_url, user.handle);
+
+    let document = JsonResume {
+        schema: Some(resume::SCHEMA_URL.to_string()),
+        basics: Basics {
+            name: Some(user.full_name),
+            label: profile.as_ref().and_then(|p| p.headline.clone()),
+            image: user.profile_picture_url.map(|url| state.absolute_url(&url)),
+            email: Some(user.email),
+            url: profile.as_ref().and_then(|p| p.website.clone()),
+            summary: profile.as_ref().and_then(|p| p.summary.clone()),
+            location: profile.as_ref().and_then(|p| p.location.clone()).map(|address| Location {
+                address: Some(address),
+                ..Default::default()
+            }),
+            profiles: vec![SocialProfile {
+                network: Some("TrueLink".to_string()),
+                username: Some(user.handle),
+                url: Some(profile_url.clone()),
+            }],
+        },
+        work: positions.iter().map(resume::work_from_position).collect(),
+        education: education.iter().map(resume::education_from_entry).collect(),
+        skills: ranked
+            .into_iter()
+            .map(|skill| ResumeSkill { name: Some(skill.name), keywords: Vec::new() })
+            .collect(),
+        meta: Some(Meta {
+            canonical: Some(profile_url),
+            version: Some(resume::SCHEMA_VERSION.to_string()),
+            last_modified: Some(user.updated_at.to_rfc3339()),
+        }),
+    };
+
+    Ok(Json(document))
+}
+
+/// Compares a JSON Resume document with the caller's profile and, unless
+/// this is a dry run, adds what's new. Import only ever adds or overwrites
+/// profile fields; nothing already on the profile is deleted.
+pub async fn import_profile(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ImportQuery>,
+    Json(document): Json<JsonResume>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    let dry_run = query.dry_run.unwrap_or(true);
+
+    let mut skipped = Vec::new();
+
+    // Profile fields that differ from what's stored
+    let current = load_profile(&state.pool, current_user_id).await.map_err(internal_error)?;
+    let (fields, errors) = resume::profile_fields_from_basics(&document.basics);
+    skipped.extend(errors);
+
+    let profile_changes: Vec<FieldChange> = fields
+        .into_iter()
+        .filter_map(|(field, imported)| {
+            let current = current.as_ref().and_then(|p| match field {
+                "headline" => p.headline.clone(),
+                "summary" => p.summary.clone(),
+                "location" => p.location.clone(),
+                _ => p.website.clone(),
+            });
+            (current.as_deref() != Some(imported.as_str())).then_some(FieldChange { field, current, imported })
+        })
+        .collect();
+
+    // Positions match on company, title and start month
+    let existing_positions = experience::list_user_positions(&state.pool, current_user_id)
+        .await
+        .map_err(internal_error)?;
+    let mut seen: HashSet<(String, String, i32, i32)> = existing_positions
+        .iter()
+        .map(|p| (organizations::normalize_name(&p.company_name), p.title.to_lowercase(), p.start_year, p.start_month))
+        .collect();
+
+    let mut positions = EntryDiff { add: Vec::new(), unchanged: 0 };
+    for (index, work) in document.work.iter().enumerate() {
+        match resume::position_from_work(work) {
+            Ok(position) => {
+                let key = (
+                    organizations::normalize_name(&position.company_name),
+                    position.title.to_lowercase(),
+                    position.start_year,
+                    position.start_month,
+                );
+                if seen.insert(key) {
+                    positions.add.push(position);
+                } else {
+                    positions.unchanged += 1;
+                }
+            }
+            Err(reason) => skipped.push(format!("work[{}]: {}", index, reason)),
+        }
+    }
+
+    // Education matches on school, degree and start year
+    let existing_education = experience::list_user_education(&state.pool, current_user_id)
+        .await
+        .map_err(internal_error)?;
+    let mut seen: HashSet<(String, Option<String>, Option<i32>)> = existing_education
+        .iter()
+        .map(|e| (organizations::normalize_name(&e.school_name), e.degree.as_ref().map(|d| d.to_lowercase()), e.start_year))
+        .collect();
+
+    let mut education = EntryDiff { add: Vec::new(), unchanged: 0 };
+    for (index, entry) in document.education.iter().enumerate() {
+        match resume::education_from_resume(entry) {
+            Ok(entry) => {
+                let key = (
+                    organizations::normalize_name(&entry.school_name),
+                    entry.degree.as_ref().map(|d| d.to_lowercase()),
+                    entry.start_year,
+                );
+                if seen.insert(key) {
+                    education.add.push(entry);
+                } else {
+                    education.unchanged += 1;
+                }
+            }
+            Err(reason) => skipped.push(format!("education[{}]: {}", index, reason)),
+        }
+    }
+
+    // Skills match through aliases, so "JS" counts as the user's "JavaScript"
+    let owned: Vec<String> = sqlx::query_scalar(
+        "SELECT sa.alias
+         FROM skill_aliases sa
+         JOIN user_skills us ON us.skill_id = sa.skill_id
+         WHERE us.user_id = $1"
+    )
+        .bind(current_user_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+    let mut seen: HashSet<String> = owned.into_iter().collect();
+
+    let mut skill_diff = EntryDiff { add: Vec::new(), unchanged: 0 };
+    for name in resume::skill_names(&document.skills) {
+        if seen.insert(skills::normalize_name(&name)) {
+            skill_diff.add.push(name);
+        } else {
+            skill_diff.unchanged += 1;
+        }
+    }
+
+    if !dry_run {
+        let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+        if !profile_changes.is_empty() {
+            // Imported fields replace the stored ones; the rest stay as they are
+            let mut fields = profile_history::lock_current(&mut tx, current_user_id)
+                .await
+                .map_err(internal_error)?;
+            for change in &profile_changes {
+                let slot = match change.field {
+                    "headline" => &mut fields.headline,
+                    "summary" => &mut fields.summary,
+                    "location" => &mut fields.location,
+                    _ => &mut fields.website,
+                };
+                *slot = Some(change.imported.clone());
+            }
+
+            profile_history::save(&mut tx, current_user_id, current_user_id, &fields, None)
+                .await
+                .map_err(internal_error)?;
+        }
+
+        for position in &positions.add {
+            experience::insert_position(&mut tx, current_user_id, position)
+                .await
+                .map_err(internal_error)?;
+        }
+
+        for entry in &education.add {
+            experience::insert_education(&mut tx, current_user_id, entry)
+                .await
+                .map_err(internal_error)?;
+        }
+
+        for name in &skill_diff.add {
+            skills::add_to_user(&mut tx, current_user_id, name)
+                .await
+                .map_err(internal_error)?;
+        }
+
+        tx.commit().await.map_err(internal_error)?;
+    }
+
+    Ok::<_, (StatusCode, String)>(Json(ImportDiff {
+        applied: !dry_run,
+        profile: profile_changes,
+        positions,
+        education,
+        skills: skill_diff,
+        skipped,
+    }))
+}
@@ -0,0 +1,167 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use image::{ImageFormat, Luma};
+use qrcode::{render::svg, QrCode};
+use serde::Deserialize;
+use std::io::Cursor;
+use uuid::Uuid;
+
+use crate::models::ConnectionRequest;
+use crate::privacy;
+use crate::relationships;
+use crate::routes::{internal_error, profile, require_user, AppState};
+use crate::vcard::VCard;
+
+const DEFAULT_QR_SIZE: u32 = 512;
+const MIN_QR_SIZE: u32 = 128;
+const MAX_QR_SIZE: u32 = 2048;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    Png,
+    Svg,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QrQuery {
+    pub format: Option<QrFormat>,
+    pub size: Option<u32>,
+}
+
+/// A profile as a vCard 4.0, holding only what the viewer may see.
+pub async fn get_vcard(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, String)> {
+    let viewer_id = crate::auth::middleware::get_user_id_from_headers(&headers);
+
+    let details = profile::load_profile_details(&state.pool, viewer_id, user_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, "Profile not found".to_string()))?;
+
+    let view = &details.profile;
+    let user = &view.user;
+    let current_position = details.positions.iter().find(|position| position.is_current);
+
+    let mut card = VCard::new(&user.full_name);
+    card.uri("UID", &format!("urn:uuid:{}", user.id));
+
+    // Family name last; a masked name like "Carol K." splits the same way
+    let (given, family) = user.full_name.rsplit_once(' ').unwrap_or((&user.full_name, ""));
+    card.structured("N", &[family, given, "", "", ""]);
+
+    if let Some(email) = &user.email {
+        card.text("EMAIL", email);
+    }
+    if let Some(position) = current_position {
+        card.text("TITLE", &position.title);
+        card.text("ORG", &position.company_name);
+    } else if let Some(headline) = &user.headline {
+        card.text("TITLE", headline);
+    }
+    if let Some(location) = &view.location {
+        card.structured("ADR", &["", "", "", location, "", "", ""]);
+    }
+    if let Some(summary) = &view.summary {
+        card.text("NOTE", summary);
+    }
+    if let Some(picture) = &user.profile_picture_url {
+        card.uri("PHOTO", &state.absolute_url(picture));
+    }
+    if let Some(website) = &view.website {
+        card.uri("URL", website);
+    }
+    card.uri("URL", &format!("{}/in/{}", state.public_base_url, user.handle));
+    card.uri("SOURCE", &format!("{}/api/profile/{}/vcard", state.public_base_url, user.id));
+    card.text("REV", &view.updated_at.format("%Y%m%dT%H%M%SZ").to_string());
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/vcard; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.vcf\"", user.handle)),
+        ],
+        card.build(),
+    )
+        .into_response())
+}
+
+/// A QR code for the caller's share link, as PNG (default) or SVG.
+pub async fn get_my_qr(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<QrQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let current_user_id = require_user(&headers)?;
+
+    let token = state
+        .auth_service
+        .generate_share_token(current_user_id)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let link = format!("{}/connect/{}", state.public_base_url, token);
+
+    let code = QrCode::new(link.as_bytes()).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let size = query.size.unwrap_or(DEFAULT_QR_SIZE).clamp(MIN_QR_SIZE, MAX_QR_SIZE);
+
+    // Every code is freshly signed, so caching one would only serve a stale expiry
+    let response = match query.format.unwrap_or(QrFormat::Png) {
+        QrFormat::Svg => {
+            let svg = code.render::<svg::Color>().min_dimensions(size, size).build();
+            ([(header::CONTENT_TYPE, "image/svg+xml"), (header::CACHE_CONTROL, "no-store")], svg).into_response()
+        }
+        QrFormat::Png => {
+            let image = code.render::<Luma<u8>>().min_dimensions(size, size).build();
+            let mut png = Vec::new();
+            image
+                .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            ([(header::CONTENT_TYPE, "image/png"), (header::CACHE_CONTROL, "no-store")], png).into_response()
+        }
+    };
+
+    Ok(response)
+}
+
+/// Where a scanned QR code lands. For a logged-in user it returns the
+/// owner's card and the connection request to pre-fill, along with where
+/// the two already stand so the client can skip the form.
+pub async fn open_share_link(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let owner_id = state
+        .auth_service
+        .verify_share_token(&token)
+        .ok_or((StatusCode::NOT_FOUND, "This link is invalid or has expired".to_string()))?;
+
+    let owner = privacy::user_cards(&state.pool, Some(current_user_id), &[owner_id])
+        .await
+        .map_err(internal_error)?
+        .remove(&owner_id)
+        .ok_or((StatusCode::NOT_FOUND, "This link is invalid or has expired".to_string()))?;
+
+    let mut conn = state.pool.acquire().await.map_err(internal_error)?;
+    let status = relationships::find_pair(&mut conn, current_user_id, owner_id)
+        .await
+        .map_err(internal_error)?
+        .map(|connection| connection.status_for_as_of(current_user_id, state.request_policy.request_expiry));
+
+    let request = (current_user_id != owner_id && status.is_none())
+        .then_some(ConnectionRequest { receiver_id: owner_id, note: None });
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "user": owner,
+        "own_profile": current_user_id == owner_id,
+        "connection_status": status,
+        "request": request
+    })))
+}
@@ -0,0 +1,322 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::middleware::get_user_id_from_headers;
+use crate::models::{AddSkillRequest, Endorsement, Endorser, MergeSkillRequest, Skill};
+use crate::pagination::{PageQuery, TOTAL_ESTIMATE_CAP};
+use crate::privacy;
+use crate::relationships;
+use crate::routes::{internal_error, invalid_cursor, profile, require_moderator, require_user, AppState};
+use crate::skills;
+
+#[derive(Debug, Deserialize)]
+pub struct SkillSearchQuery {
+    pub q: Option<String>,
+}
+
+/// Skill autocomplete. Matches on every alias, so "js" finds JavaScript.
+pub async fn search_skills(
+    State(state): State<AppState>,
+    Query(query): Query<SkillSearchQuery>,
+) -> impl IntoResponse {
+    let normalized = skills::normalize_name(&query.q.unwrap_or_default());
+    if normalized.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Search query is required".to_string()));
+    }
+
+    let pattern = format!("{}%", normalized.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+
+    let skills: Vec<Skill> = sqlx::query_as(
+        "SELECT s.*
+         FROM skills s
+         WHERE s.id IN (SELECT skill_id FROM skill_aliases WHERE alias LIKE $1)
+         ORDER BY (SELECT COUNT(*) FROM user_skills us WHERE us.skill_id = s.id) DESC, s.name
+         LIMIT 10"
+    )
+        .bind(pattern)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(serde_json::json!({ "skills": skills })))
+}
+
+pub async fn list_my_skills(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let skills = skills::ranked_skills(&state.pool, current_user_id)
+        .await
+        .map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({ "skills": skills })))
+}
+
+pub async fn add_skill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<AddSkillRequest>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    if let Err(validation_errors) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, format!("Validation failed: {:?}", validation_errors)));
+    }
+    if skills::normalize_name(&payload.name).is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Skill name is required".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    let skill_id = skills::add_to_user(&mut tx, current_user_id, &payload.name)
+        .await
+        .map_err(internal_error)?;
+
+    let skill: Skill = sqlx::query_as("SELECT * FROM skills WHERE id = $1")
+        .bind(skill_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok((StatusCode::CREATED, Json(skill)))
+}
+
+pub async fn remove_skill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(skill_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let result = sqlx::query("DELETE FROM user_skills WHERE user_id = $1 AND skill_id = $2")
+        .bind(current_user_id)
+        .bind(skill_id)
+        .execute(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Skill not found on your profile".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn require_profile_access(
+    state: &AppState,
+    headers: &HeaderMap,
+    user_id: Uuid,
+) -> Result<Option<Uuid>, (StatusCode, String)> {
+    let viewer_id = get_user_id_from_headers(headers);
+
+    let visible = profile::can_view_profile(&state.pool, viewer_id, user_id)
+        .await
+        .map_err(internal_error)?;
+
+    if !visible {
+        return Err((StatusCode::NOT_FOUND, "Profile not found".to_string()));
+    }
+
+    Ok(viewer_id)
+}
+
+pub async fn list_user_skills(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    require_profile_access(&state, &headers, user_id).await?;
+
+    let skills = skills::ranked_skills(&state.pool, user_id)
+        .await
+        .map_err(internal_error)?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({ "skills": skills })))
+}
+
+pub async fn list_endorsements(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((user_id, skill_id)): Path<(Uuid, Uuid)>,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    require_profile_access(&state, &headers, user_id).await?;
+
+    let after: Option<(chrono::DateTime<chrono::Utc>, Uuid)> = state.cursors
+        .decode("endorsements", &page)
+        .map_err(invalid_cursor)?;
+
+    let viewer_id = get_user_id_from_headers(&headers);
+
+    // Endorsers blocked either way with the viewer are left out of the page
+    // and the total alike
+    let rows: Vec<(Uuid, Uuid, Option<String>, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+        "SELECT e.id, u.id, u.verification_tier, e.created_at
+         FROM endorsements e
+         JOIN users u ON u.id = e.endorser_id
+         WHERE e.user_id = $1 AND e.skill_id = $2
+           AND NOT EXISTS (
+                   SELECT 1 FROM user_blocks b
+                   WHERE (b.blocker_id = $6 AND b.blocked_id = u.id) OR (b.blocker_id = u.id AND b.blocked_id = $6)
+               )
+           AND ($3::timestamptz IS NULL OR (e.created_at, e.id) < ($3, $4))
+         ORDER BY e.created_at DESC, e.id DESC
+         LIMIT $5"
+    )
+        .bind(user_id)
+        .bind(skill_id)
+        .bind(after.map(|(created_at, _)| created_at))
+        .bind(after.map(|(_, id)| id))
+        .bind(page.fetch_limit())
+        .bind(viewer_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let total_estimate: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (
+             SELECT 1 FROM endorsements e
+             WHERE e.user_id = $1 AND e.skill_id = $2
+               AND NOT EXISTS (
+                       SELECT 1 FROM user_blocks b
+                       WHERE (b.blocker_id = $3 AND b.blocked_id = e.endorser_id)
+                          OR (b.blocker_id = e.endorser_id AND b.blocked_id = $3)
+                   )
+             LIMIT $4
+         ) endorsers"
+    )
+        .bind(user_id)
+        .bind(skill_id)
+        .bind(viewer_id)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let (rows, next_cursor) = state.cursors.page("endorsements", &page, rows, |row| (row.3, row.0));
+
+    let endorsers: Vec<Endorser> = privacy::attach_cards(&state.pool, viewer_id, rows, |row| row.1)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .map(|((endorsement_id, _, verification_tier, endorsed_at), endorser)| Endorser {
+            endorsement_id,
+            endorser,
+            verification_tier,
+            endorsed_at,
+        })
+        .collect();
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "endorsements": endorsers,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
+
+/// Only accepted connections may endorse, and only skills the user lists.
+pub async fn endorse_skill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((user_id, skill_id)): Path<(Uuid, Uuid)>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    if current_user_id == user_id {
+        return Err((StatusCode::BAD_REQUEST, "Cannot endorse your own skills".to_string()));
+    }
+
+    let connected = relationships::are_connected(&state.pool, current_user_id, user_id)
+        .await
+        .map_err(internal_error)?;
+
+    if !connected {
+        return Err((StatusCode::FORBIDDEN, "You can only endorse your connections".to_string()));
+    }
+
+    let endorsement: Option<Endorsement> = sqlx::query_as(
+        "INSERT INTO endorsements (endorser_id, user_id, skill_id)
+         SELECT $1, us.user_id, us.skill_id
+         FROM user_skills us
+         WHERE us.user_id = $2 AND us.skill_id = $3
+         ON CONFLICT (endorser_id, user_id, skill_id) DO UPDATE SET endorser_id = EXCLUDED.endorser_id
+         RETURNING *"
+    )
+        .bind(current_user_id)
+        .bind(user_id)
+        .bind(skill_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let endorsement = endorsement
+        .ok_or((StatusCode::NOT_FOUND, "Skill not found on this profile".to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(endorsement)))
+}
+
+pub async fn remove_endorsement(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((user_id, skill_id)): Path<(Uuid, Uuid)>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let result = sqlx::query(
+        "DELETE FROM endorsements WHERE endorser_id = $1 AND user_id = $2 AND skill_id = $3"
+    )
+        .bind(current_user_id)
+        .bind(user_id)
+        .bind(skill_id)
+        .execute(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Endorsement not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Moderators fold a duplicate skill into the one it should have been an
+/// alias of, e.g. a user-created "Javascript Dev" into JavaScript.
+pub async fn merge_skill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(skill_id): Path<Uuid>,
+    Json(payload): Json<MergeSkillRequest>,
+) -> impl IntoResponse {
+    require_moderator(&state.pool, &headers).await?;
+
+    if payload.into == skill_id {
+        return Err((StatusCode::BAD_REQUEST, "A skill can't be merged into itself".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+
+    if !skills::merge(&mut tx, skill_id, payload.into).await.map_err(internal_error)? {
+        return Err((StatusCode::NOT_FOUND, "Skill not found".to_string()));
+    }
+
+    let merged: Skill = sqlx::query_as("SELECT * FROM skills WHERE id = $1")
+        .bind(payload.into)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+
+    tx.commit().await.map_err(internal_error)?;
+
+    Ok(Json(merged))
+}
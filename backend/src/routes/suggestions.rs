@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::UserCard;
+use crate::pagination::{PageQuery, TOTAL_ESTIMATE_CAP};
+use crate::privacy::{self, Field, ViewerAccess};
+use crate::routes::{internal_error, invalid_cursor, require_user, AppState};
+use crate::suggestions;
+
+#[derive(Debug, FromRow)]
+struct StoredSuggestion {
+    suggested_id: Uuid,
+    score: f64,
+    mutual_connections: i32,
+    shared_organizations: i32,
+    same_location: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Suggestion {
+    user: UserCard,
+    score: f64,
+    mutual_connections: i32,
+    /// Left out, like `same_location`, when the suggested user's profile
+    /// fields are hidden from the caller
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shared_organizations: Option<i32>,
+    /// Left out when the suggested user's location is hidden from the caller
+    #[serde(skip_serializing_if = "Option::is_none")]
+    same_location: Option<bool>,
+}
+
+/// The caller's precomputed suggestions, best first. Anyone the caller has
+/// since connected with, asked, blocked or dismissed is dropped even if the
+/// job hasn't run again yet.
+pub async fn list_suggestions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(page): Query<PageQuery>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+    let after: Option<(f64, Uuid)> = state.cursors
+        .decode("suggestions", &page)
+        .map_err(invalid_cursor)?;
+
+    let still_open = "WHERE s.user_id = $1
+           AND NOT EXISTS (
+                   SELECT 1 FROM connections c
+                   WHERE LEAST(c.sender_id, c.receiver_id) = LEAST($1, s.suggested_id)
+                     AND GREATEST(c.sender_id, c.receiver_id) = GREATEST($1, s.suggested_id)
+               )
+           AND NOT EXISTS (
+                   SELECT 1 FROM dismissed_suggestions d WHERE d.user_id = $1 AND d.dismissed_id = s.suggested_id
+               )
+           AND NOT EXISTS (
+                   SELECT 1 FROM user_blocks b
+                   WHERE (b.blocker_id = $1 AND b.blocked_id = s.suggested_id)
+                      OR (b.blocker_id = s.suggested_id AND b.blocked_id = $1)
+               )";
+
+    let stored: Vec<StoredSuggestion> = sqlx::query_as(&format!(
+        "SELECT s.suggested_id, s.score, s.mutual_connections, s.shared_organizations, s.same_location
+         FROM connection_suggestions s
+         {still_open}
+           AND ($2::float8 IS NULL OR s.score < $2 OR (s.score = $2 AND s.suggested_id > $3))
+         ORDER BY s.score DESC, s.suggested_id
+         LIMIT $4"
+    ))
+        .bind(current_user_id)
+        .bind(after.map(|(score, _)| score))
+        .bind(after.map(|(_, id)| id))
+        .bind(page.fetch_limit())
+        .fetch_all(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let total_estimate: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM (SELECT 1 FROM connection_suggestions s {still_open} LIMIT $2) remaining"
+    ))
+        .bind(current_user_id)
+        .bind(TOTAL_ESTIMATE_CAP)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    let (stored, next_cursor) = state.cursors.page("suggestions", &page, stored, |s| (s.score, s.suggested_id));
+
+    let ids: Vec<Uuid> = stored.iter().map(|suggestion| suggestion.suggested_id).collect();
+    let access = ViewerAccess::load(&state.pool, Some(current_user_id), &ids)
+        .await
+        .map_err(internal_error)?;
+
+    // Cards also leave out anyone who deleted their account since the last run
+    let suggestions: Vec<Suggestion> = privacy::attach_cards(&state.pool, Some(current_user_id), stored, |s| s.suggested_id)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .map(|(stored, user)| {
+            let profile_visible = access.can_see(stored.suggested_id, Field::ProfileFields);
+            Suggestion {
+                user,
+                score: stored.score,
+                mutual_connections: stored.mutual_connections,
+                shared_organizations: profile_visible.then_some(stored.shared_organizations),
+                same_location: profile_visible.then_some(stored.same_location),
+            }
+        })
+        .collect();
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "suggestions": suggestions,
+        "next_cursor": next_cursor,
+        "total_estimate": total_estimate
+    })))
+}
+
+pub async fn dismiss_suggestion(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = require_user(&headers)?;
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE id = $1)")
+        .bind(user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal_error)?;
+
+    if !exists || user_id == current_user_id {
+        return Err((StatusCode::NOT_FOUND, "User not found".to_string()));
+    }
+
+    suggestions::dismiss(&state.pool, current_user_id, user_id)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::middleware::get_user_id_from_headers;
+use crate::models::{TrustScore, Vouch};
+use crate::relationships;
+use crate::routes::AppState;
+
+pub async fn get_trust_score(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let score: Option<TrustScore> = sqlx::query_as(
+        "SELECT user_id, score, tier_component, age_component, vouch_component,
+                propagation_component, computed_at
+         FROM trust_scores WHERE user_id = $1"
+    )
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let score = score.ok_or((StatusCode::NOT_FOUND, "Trust score not computed yet".to_string()))?;
+
+    let (vouch_count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM vouches WHERE vouchee_id = $1")
+        .bind(user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok::<_, (StatusCode, String)>(Json(serde_json::json!({
+        "trust": score,
+        "vouch_count": vouch_count
+    })))
+}
+
+/// Vouching is limited to accepted connections; it feeds the next recompute.
+pub async fn vouch_for_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = get_user_id_from_headers(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))?;
+
+    if current_user_id == user_id {
+        return Err((StatusCode::BAD_REQUEST, "Cannot vouch for yourself".to_string()));
+    }
+
+    let connected = relationships::are_connected(&state.pool, current_user_id, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if !connected {
+        return Err((StatusCode::FORBIDDEN, "You can only vouch for your connections".to_string()));
+    }
+
+    let vouch: Vouch = sqlx::query_as(
+        "INSERT INTO vouches (voucher_id, vouchee_id)
+         VALUES ($1, $2)
+         ON CONFLICT (voucher_id, vouchee_id) DO UPDATE SET voucher_id = EXCLUDED.voucher_id
+         RETURNING *"
+    )
+        .bind(current_user_id)
+        .bind(user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(vouch)))
+}
+
+pub async fn withdraw_vouch(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let current_user_id = get_user_id_from_headers(&headers)
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))?;
+
+    let result = sqlx::query("DELETE FROM vouches WHERE voucher_id = $1 AND vouchee_id = $2")
+        .bind(current_user_id)
+        .bind(user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Vouch not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,230 @@
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::{RankedSkill, VerificationTier};
+
+/// Lookup key for skill names and aliases: lowercase with collapsed whitespace.
+/// Punctuation is kept because it matters for names like "C++" and "C#".
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// How much one endorsement counts, by the endorser's verification tier.
+pub fn endorsement_weight(tier: VerificationTier) -> f64 {
+    match tier {
+        VerificationTier::Standard => 1.0,
+        VerificationTier::EmailVerified => 1.5,
+        VerificationTier::IdentityVerified => 2.5,
+    }
+}
+
+/// Resolves a free-text skill name through its aliases, creating a new skill
+/// (and its own alias) when nothing matches.
+pub async fn resolve(conn: &mut PgConnection, name: &str) -> Result<Uuid, sqlx::Error> {
+    let normalized = normalize_name(name);
+
+    let existing: Option<Uuid> = sqlx::query_scalar("SELECT skill_id FROM skill_aliases WHERE alias = $1")
+        .bind(&normalized)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    if let Some(skill_id) = existing {
+        return Ok(skill_id);
+    }
+
+    let skill_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO skills (name, normalized_name)
+         VALUES ($1, $2)
+         ON CONFLICT (normalized_name) DO UPDATE SET normalized_name = EXCLUDED.normalized_name
+         RETURNING id"
+    )
+        .bind(name.split_whitespace().collect::<Vec<_>>().join(" "))
+        .bind(&normalized)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    sqlx::query("INSERT INTO skill_aliases (alias, skill_id) VALUES ($1, $2) ON CONFLICT (alias) DO NOTHING")
+        .bind(&normalized)
+        .bind(skill_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(skill_id)
+}
+
+/// Folds the skill `source` into `target`, for a duplicate that should have
+/// been an alias of it: its aliases, listings and endorsements move over and
+/// `source` goes away. False when either skill doesn't exist.
+pub async fn merge(conn: &mut PgConnection, source: Uuid, target: Uuid) -> Result<bool, sqlx::Error> {
+    let found: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM (SELECT 1 FROM skills WHERE id IN ($1, $2) FOR UPDATE) locked")
+        .bind(source)
+        .bind(target)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if found != 2 {
+        return Ok(false);
+    }
+
+    sqlx::query("SELECT merge_skills($1, $2)")
+        .bind(source)
+        .bind(target)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(true)
+}
+
+/// Lists a skill on the user's profile by name, resolving aliases. Adding a
+/// skill the user already has is a no-op.
+pub async fn add_to_user(conn: &mut PgConnection, user_id: Uuid, name: &str) -> Result<Uuid, sqlx::Error> {
+    let skill_id = resolve(conn, name).await?;
+
+    sqlx::query(
+        "INSERT INTO user_skills (user_id, skill_id) VALUES ($1, $2)
+         ON CONFLICT (user_id, skill_id) DO NOTHING"
+    )
+        .bind(user_id)
+        .bind(skill_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(skill_id)
+}
+
+/// The user's skills, ranked by endorsements weighted by each endorser's tier.
+pub async fn ranked_skills(pool: &PgPool, user_id: Uuid) -> Result<Vec<RankedSkill>, sqlx::Error> {
+    let skills: Vec<(Uuid, String)> = sqlx::query_as(
+        "SELECT s.id, s.name
+         FROM user_skills us
+         JOIN skills s ON s.id = us.skill_id
+         WHERE us.user_id = $1
+         ORDER BY us.created_at"
+    )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    let endorsement_tiers: Vec<(Uuid, Option<String>, i64)> = sqlx::query_as(
+        "SELECT e.skill_id, u.verification_tier, COUNT(*)
+         FROM endorsements e
+         JOIN users u ON u.id = e.endorser_id
+         WHERE e.user_id = $1
+         GROUP BY e.skill_id, u.verification_tier"
+    )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    let mut totals: HashMap<Uuid, (i64, f64)> = HashMap::new();
+    for (skill_id, tier, count) in endorsement_tiers {
+        let tier = tier.as_deref()
+            .and_then(VerificationTier::parse)
+            .unwrap_or(VerificationTier::Standard);
+        let entry = totals.entry(skill_id).or_default();
+        entry.0 += count;
+        entry.1 += count as f64 * endorsement_weight(tier);
+    }
+
+    let mut ranked: Vec<RankedSkill> = skills
+        .into_iter()
+        .map(|(skill_id, name)| {
+            let (endorsement_count, weighted_score) = totals.get(&skill_id).copied().unwrap_or_default();
+            RankedSkill { skill_id, name, endorsement_count, weighted_score }
+        })
+        .collect();
+
+    // Stable sort keeps insertion order among equally endorsed skills
+    ranked.sort_by(|a, b| b.weighted_score.total_cmp(&a.weighted_score));
+
+    Ok(ranked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn user(pool: &PgPool, name: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (email, full_name, handle) VALUES ($1, $2, $3) RETURNING id")
+            .bind(format!("{}@example.com", name))
+            .bind(name)
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn normalizes_case_and_whitespace() {
+        assert_eq!(normalize_name("Rust"), "rust");
+        assert_eq!(normalize_name("  Machine   Learning \t"), "machine learning");
+        assert_eq!(normalize_name("Project\nManagement"), "project management");
+    }
+
+    #[test]
+    fn keeps_punctuation() {
+        assert_eq!(normalize_name("C++"), "c++");
+        assert_eq!(normalize_name("Node.js"), "node.js");
+    }
+
+    #[test]
+    fn blank_names_normalize_to_empty() {
+        assert_eq!(normalize_name("   "), "");
+    }
+
+    #[test]
+    fn weights_endorsements_by_tier() {
+        assert!(endorsement_weight(VerificationTier::IdentityVerified) > endorsement_weight(VerificationTier::EmailVerified));
+        assert!(endorsement_weight(VerificationTier::EmailVerified) > endorsement_weight(VerificationTier::Standard));
+    }
+
+    #[sqlx::test]
+    async fn merge_folds_a_duplicate_into_its_skill(pool: PgPool) {
+        let (endorser, owner, other) = (user(&pool, "endorser").await, user(&pool, "owner").await, user(&pool, "other").await);
+        let mut conn = pool.acquire().await.unwrap();
+
+        let target = resolve(&mut conn, "JS").await.unwrap();
+        let source = add_to_user(&mut conn, owner, "Javascript Dev").await.unwrap();
+        assert_ne!(source, target);
+        add_to_user(&mut conn, owner, "JavaScript").await.unwrap();
+        add_to_user(&mut conn, other, "javascript  dev").await.unwrap();
+
+        for skill_id in [source, target] {
+            sqlx::query("INSERT INTO endorsements (endorser_id, user_id, skill_id) VALUES ($1, $2, $3)")
+                .bind(endorser)
+                .bind(owner)
+                .bind(skill_id)
+                .execute(&mut *conn)
+                .await
+                .unwrap();
+        }
+
+        assert!(merge(&mut conn, source, target).await.unwrap());
+
+        // The duplicate's name now resolves to the skill it was merged into
+        assert_eq!(resolve(&mut conn, "Javascript Dev").await.unwrap(), target);
+
+        let listings: Vec<(Uuid, Uuid)> = sqlx::query_as("SELECT user_id, skill_id FROM user_skills ORDER BY user_id")
+            .fetch_all(&mut *conn)
+            .await
+            .unwrap();
+        let mut expected = vec![(owner, target), (other, target)];
+        expected.sort();
+        assert_eq!(listings, expected);
+
+        let endorsements: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM endorsements WHERE user_id = $1 AND skill_id = $2")
+            .bind(owner)
+            .bind(target)
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(endorsements, 1);
+
+        // The duplicate is gone, so merging it again finds nothing
+        assert!(!merge(&mut conn, source, target).await.unwrap());
+    }
+}
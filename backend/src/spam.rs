@@ -0,0 +1,111 @@
+//! Cheap checks for obvious spam in short free-text messages.
+
+const SPAM_PHRASES: &[&str] = &[
+    "crypto", "bitcoin", "forex", "investment opportunity", "guaranteed return", "make money",
+    "earn $", "work from home", "whatsapp me", "telegram me", "click here", "limited offer",
+    "free gift", "dm me for",
+];
+
+const MAX_LINKS: usize = 1;
+const MAX_REPEATED_CHARS: usize = 6;
+
+/// `text` as space-separated lowercase words, with a space at each end so
+/// phrases can be matched on word boundaries. `$` stands as a word of its own.
+fn words(text: &str) -> String {
+    let mut words = String::from(" ");
+    for c in text.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            words.push(c);
+        } else if c == '$' {
+            if !words.ends_with(' ') {
+                words.push(' ');
+            }
+            words.push_str("$ ");
+        } else if !words.ends_with(' ') {
+            words.push(' ');
+        }
+    }
+    if !words.ends_with(' ') {
+        words.push(' ');
+    }
+    words
+}
+
+fn is_link(token: &str) -> bool {
+    let token = token.trim_start_matches(|c: char| !c.is_alphanumeric());
+    token.contains("http://") || token.contains("https://") || token.starts_with("www.")
+}
+
+/// Why `text` looks like spam, if it does.
+pub fn check(text: &str) -> Result<(), &'static str> {
+    let lower = text.to_lowercase();
+
+    let text_words = words(text);
+    if SPAM_PHRASES.iter().any(|phrase| text_words.contains(&words(phrase))) {
+        return Err("Message contains phrases commonly used in spam");
+    }
+
+    let links = lower.split_whitespace().filter(|token| is_link(token)).count();
+    if links > MAX_LINKS {
+        return Err("Message contains too many links");
+    }
+
+    // "!!!!!!!" and "heyyyyyyy"
+    let mut run = 0;
+    let mut previous = None;
+    for c in lower.chars() {
+        run = if Some(c) == previous { run + 1 } else { 1 };
+        previous = Some(c);
+        if run > MAX_REPEATED_CHARS {
+            return Err("Message repeats the same character too often");
+        }
+    }
+
+    // Shouting: mostly capitals across a message long enough to tell
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() >= 20 && letters.iter().filter(|c| c.is_uppercase()).count() * 10 > letters.len() * 7 {
+        return Err("Message is mostly capital letters");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_an_ordinary_note() {
+        assert_eq!(check("Great meeting you at RustConf, let's keep in touch!"), Ok(()));
+    }
+
+    #[test]
+    fn counts_one_url_once() {
+        assert_eq!(check("Slides are at https://www.rustconf.com/talks"), Ok(()));
+        assert_eq!(check("See www.example.com"), Ok(()));
+        assert!(check("https://a.example and https://b.example").is_err());
+        assert!(check("(www.a.example) http://b.example").is_err());
+    }
+
+    #[test]
+    fn matches_phrases_on_word_boundaries() {
+        assert_eq!(check("I work on cryptography at the university"), Ok(()));
+        assert!(check("Let's talk crypto").is_err());
+        assert!(check("CRYPTO!").is_err());
+        assert!(check("You could earn $500 a day").is_err());
+        assert!(check("Please click   here").is_err());
+        assert_eq!(check("The clicker was here"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_long_character_runs() {
+        assert_eq!(check("Hey!!!!!!"), Ok(()));
+        assert!(check("Heyyyyyyyy").is_err());
+    }
+
+    #[test]
+    fn rejects_shouting_but_not_short_acronyms() {
+        assert!(check("PLEASE ACCEPT MY REQUEST RIGHT NOW").is_err());
+        assert_eq!(check("Met you at AWS re:Invent"), Ok(()));
+    }
+}
@@ -0,0 +1,77 @@
+pub mod local;
+pub mod s3;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+use async_trait::async_trait;
+use std::env;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    Http(String),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "storage io error: {}", e),
+            StorageError::Http(e) => write!(f, "storage http error: {}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(e: std::io::Error) -> Self {
+        StorageError::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for StorageError {
+    fn from(e: reqwest::Error) -> Self {
+        StorageError::Http(e.to_string())
+    }
+}
+
+/// Where uploaded media lives. Keys are slash-separated relative paths such as
+/// `avatars/<user>/<upload>/200.jpg`.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), StorageError>;
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// URL clients use to fetch the object.
+    fn public_url(&self, key: &str) -> String;
+}
+
+/// Directory the local backend writes to and `/media` serves from.
+pub fn local_media_root() -> String {
+    env::var("MEDIA_ROOT").unwrap_or_else(|_| "./media".to_string())
+}
+
+/// Builds the backend selected by `STORAGE_BACKEND` (`local` by default, or `s3`).
+pub fn from_env(public_base_url: &str) -> Arc<dyn Storage> {
+    match env::var("STORAGE_BACKEND").unwrap_or_else(|_| "local".to_string()).as_str() {
+        "s3" => {
+            let endpoint = env::var("S3_ENDPOINT").expect("S3_ENDPOINT must be set");
+            let bucket = env::var("S3_BUCKET").expect("S3_BUCKET must be set");
+
+            Arc::new(S3Storage::new(
+                endpoint.clone(),
+                bucket.clone(),
+                env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                env::var("S3_ACCESS_KEY").expect("S3_ACCESS_KEY must be set"),
+                env::var("S3_SECRET_KEY").expect("S3_SECRET_KEY must be set"),
+                env::var("S3_PUBLIC_URL")
+                    .unwrap_or_else(|_| format!("{}/{}", endpoint.trim_end_matches('/'), bucket)),
+            ))
+        }
+        _ => Arc::new(LocalStorage::new(
+            local_media_root(),
+            format!("{}{}", public_base_url.trim_end_matches('/'), LocalStorage::URL_PREFIX),
+        )),
+    }
+}
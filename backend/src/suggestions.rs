@@ -0,0 +1,259 @@
+//! "People you may know". Candidates come from friends of friends and from
+//! people who worked or studied at the same organizations; location,
+//! verification, trust and profile completeness only reorder them. Scores
+//! are computed for everyone at once by a background job and read back from
+//! `connection_suggestions`.
+
+use sqlx::{FromRow, PgPool};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::completeness;
+use crate::models::VerificationTier;
+use crate::network;
+use crate::privacy::{Field, ViewerAccess};
+
+/// Suggestions kept per user.
+const MAX_PER_USER: usize = 50;
+
+const MUTUAL_WEIGHT: f64 = 3.0;
+const SHARED_ORGANIZATION_WEIGHT: f64 = 2.0;
+const SAME_LOCATION_WEIGHT: f64 = 1.0;
+/// Applied to trust blended with profile completeness, scaled down from 0-100
+const PROFILE_SIGNAL_WEIGHT: f64 = 1.0;
+
+fn tier_weight(tier: VerificationTier) -> f64 {
+    match tier {
+        VerificationTier::Standard => 0.0,
+        VerificationTier::EmailVerified => 0.5,
+        VerificationTier::IdentityVerified => 1.0,
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct Candidate {
+    user_id: Uuid,
+    suggested_id: Uuid,
+    /// Every connection the two share, including ones the user can't know about
+    mutual_ids: Vec<Uuid>,
+    shared_organizations: i64,
+    same_location: bool,
+    verification_tier: Option<String>,
+    trust_score: Option<f64>,
+}
+
+impl Candidate {
+    /// `mutual_connections` counts only the shared connections the user may
+    /// see, and shared organizations and location only count when the user
+    /// may see the suggested user's profile; `completeness` is the suggested
+    /// user's profile completeness.
+    fn score(&self, mutual_connections: i64, profile_visible: bool, completeness: u32) -> f64 {
+        let tier = self
+            .verification_tier
+            .as_deref()
+            .and_then(VerificationTier::parse)
+            .unwrap_or(VerificationTier::Standard);
+
+        let shared_organizations = if profile_visible { self.shared_organizations } else { 0 };
+        let same_location = profile_visible && self.same_location;
+
+        mutual_connections as f64 * MUTUAL_WEIGHT
+            + shared_organizations as f64 * SHARED_ORGANIZATION_WEIGHT
+            + if same_location { SAME_LOCATION_WEIGHT } else { 0.0 }
+            + tier_weight(tier)
+            + completeness::ranking_signal(self.trust_score, completeness) / 100.0 * PROFILE_SIGNAL_WEIGHT
+    }
+}
+
+/// Replaces every user's suggestions with freshly scored ones. Anyone the
+/// user already has a connection row with (accepted, pending either way or
+/// rejected), anyone blocked either way and anyone dismissed is left out.
+pub async fn recompute(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let candidates: Vec<Candidate> = sqlx::query_as(
+        "WITH edges AS (
+             SELECT sender_id AS a, receiver_id AS b FROM connections WHERE status = 'accepted'
+             UNION ALL
+             SELECT receiver_id AS a, sender_id AS b FROM connections WHERE status = 'accepted'
+         ),
+         mutual AS (
+             SELECT e1.a AS user_id, e2.b AS suggested_id, ARRAY_AGG(e1.b) AS via
+             FROM edges e1
+             JOIN edges e2 ON e2.a = e1.b AND e2.b <> e1.a
+             GROUP BY e1.a, e2.b
+         ),
+         memberships AS (
+             SELECT user_id, organization_id FROM positions WHERE organization_id IS NOT NULL
+             UNION
+             SELECT user_id, organization_id FROM education WHERE organization_id IS NOT NULL
+         ),
+         shared AS (
+             SELECT m1.user_id, m2.user_id AS suggested_id, COUNT(*) AS n
+             FROM memberships m1
+             JOIN memberships m2 ON m2.organization_id = m1.organization_id AND m2.user_id <> m1.user_id
+             GROUP BY m1.user_id, m2.user_id
+         ),
+         pairs AS (
+             SELECT COALESCE(m.user_id, s.user_id) AS user_id,
+                    COALESCE(m.suggested_id, s.suggested_id) AS suggested_id,
+                    COALESCE(m.via, ARRAY[]::uuid[]) AS mutual_ids,
+                    COALESCE(s.n, 0) AS shared_organizations
+             FROM mutual m
+             FULL JOIN shared s ON s.user_id = m.user_id AND s.suggested_id = m.suggested_id
+         )
+         SELECT p.user_id, p.suggested_id, p.mutual_ids, p.shared_organizations,
+                COALESCE(LOWER(TRIM(own.location)) = LOWER(TRIM(theirs.location)), FALSE) AS same_location,
+                u.verification_tier,
+                ts.score AS trust_score
+         FROM pairs p
+         JOIN users u ON u.id = p.suggested_id
+         LEFT JOIN trust_scores ts ON ts.user_id = p.suggested_id
+         LEFT JOIN user_profiles own ON own.user_id = p.user_id
+         LEFT JOIN user_profiles theirs ON theirs.user_id = p.suggested_id
+         WHERE NOT EXISTS (
+                   SELECT 1 FROM connections c
+                   WHERE LEAST(c.sender_id, c.receiver_id) = LEAST(p.user_id, p.suggested_id)
+                     AND GREATEST(c.sender_id, c.receiver_id) = GREATEST(p.user_id, p.suggested_id)
+               )
+           AND NOT EXISTS (
+                   SELECT 1 FROM user_blocks b
+                   WHERE (b.blocker_id = p.user_id AND b.blocked_id = p.suggested_id)
+                      OR (b.blocker_id = p.suggested_id AND b.blocked_id = p.user_id)
+               )
+           AND NOT EXISTS (
+                   SELECT 1 FROM dismissed_suggestions d
+                   WHERE d.user_id = p.user_id AND d.dismissed_id = p.suggested_id
+               )"
+    )
+        .fetch_all(pool)
+        .await?;
+
+    let suggested: HashSet<Uuid> = candidates.iter().map(|candidate| candidate.suggested_id).collect();
+    let completeness = completeness::scores(pool, &suggested.into_iter().collect::<Vec<_>>()).await?;
+
+    let mut by_user: HashMap<Uuid, Vec<Candidate>> = HashMap::new();
+    for candidate in candidates {
+        by_user.entry(candidate.user_id).or_default().push(candidate);
+    }
+
+    let mut user_ids = Vec::new();
+    let mut suggested_ids = Vec::new();
+    let mut scores = Vec::new();
+    let mut mutual_connections = Vec::new();
+    let mut shared_organizations = Vec::new();
+    let mut same_locations = Vec::new();
+
+    // What each user may see of their candidates and the connections they
+    // share, loaded for everyone at once
+    let owners_by_user: HashMap<Uuid, Vec<Uuid>> = by_user
+        .iter()
+        .map(|(user_id, candidates)| {
+            let owners: HashSet<Uuid> = candidates
+                .iter()
+                .flat_map(|candidate| candidate.mutual_ids.iter().copied().chain([candidate.suggested_id]))
+                .collect();
+            (*user_id, owners.into_iter().collect())
+        })
+        .collect();
+    let mut access_by_user = ViewerAccess::load_many(pool, &owners_by_user).await?;
+
+    for (user_id, candidates) in by_user {
+        let Some(access) = access_by_user.remove(&user_id) else {
+            continue;
+        };
+
+        let mut ranked: Vec<(f64, i64, bool, Candidate)> = candidates
+            .into_iter()
+            .filter_map(|candidate| {
+                // A shared connection only counts if the user could see it
+                // on the mutual connections list
+                let visible = candidate
+                    .mutual_ids
+                    .iter()
+                    .filter(|&&via| network::edge_visible(&access, user_id, via, candidate.suggested_id))
+                    .count() as i64;
+                let profile_visible = access.can_see(candidate.suggested_id, Field::ProfileFields);
+
+                // With nothing the user may see behind it, the suggestion
+                // itself would give away a hidden connection or position
+                if visible == 0 && !(profile_visible && candidate.shared_organizations > 0) {
+                    return None;
+                }
+
+                let completeness = completeness.get(&candidate.suggested_id).copied().unwrap_or(0);
+                Some((candidate.score(visible, profile_visible, completeness), visible, profile_visible, candidate))
+            })
+            .collect();
+
+        ranked.sort_by(|(a, ..), (b, ..)| b.total_cmp(a));
+        for (score, visible, profile_visible, candidate) in ranked.into_iter().take(MAX_PER_USER) {
+            user_ids.push(candidate.user_id);
+            suggested_ids.push(candidate.suggested_id);
+            scores.push(score);
+            mutual_connections.push(visible as i32);
+            shared_organizations.push(if profile_visible { candidate.shared_organizations as i32 } else { 0 });
+            same_locations.push(profile_visible && candidate.same_location);
+        }
+    }
+
+    // Swapped in one transaction so readers never see a half-written table
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM connection_suggestions").execute(&mut *tx).await?;
+
+    sqlx::query(
+        "INSERT INTO connection_suggestions
+            (user_id, suggested_id, score, mutual_connections, shared_organizations, same_location)
+         SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::float8[], $4::int[], $5::int[], $6::bool[])"
+    )
+        .bind(&user_ids)
+        .bind(&suggested_ids)
+        .bind(&scores)
+        .bind(&mutual_connections)
+        .bind(&shared_organizations)
+        .bind(&same_locations)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(user_ids.len())
+}
+
+/// Background job: refreshes everyone's suggestions every `interval`.
+pub fn spawn_recompute_job(pool: PgPool, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            match recompute(&pool).await {
+                Ok(count) => println!("🤝 Precomputed {} connection suggestions", count),
+                Err(e) => eprintln!("Connection suggestion recompute error: {}", e),
+            }
+        }
+    });
+}
+
+/// Stops suggesting `dismissed_id` to `user_id`, now and in later runs.
+pub async fn dismiss(pool: &PgPool, user_id: Uuid, dismissed_id: Uuid) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO dismissed_suggestions (user_id, dismissed_id) VALUES ($1, $2)
+         ON CONFLICT (user_id, dismissed_id) DO NOTHING"
+    )
+        .bind(user_id)
+        .bind(dismissed_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM connection_suggestions WHERE user_id = $1 AND suggested_id = $2")
+        .bind(user_id)
+        .bind(dismissed_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await
+}
@@ -0,0 +1,212 @@
+pub mod propagation;
+
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::models::VerificationTier;
+
+const CONNECTION_EDGE_WEIGHT: f64 = 1.0;
+const VOUCH_EDGE_WEIGHT: f64 = 3.0;
+
+const TIER_WEIGHT: f64 = 0.30;
+const AGE_WEIGHT: f64 = 0.15;
+const VOUCH_WEIGHT: f64 = 0.15;
+const PROPAGATION_WEIGHT: f64 = 0.40;
+
+/// Account age at which the age component saturates.
+const MATURE_ACCOUNT_DAYS: f64 = 365.0;
+
+pub fn tier_component(tier: VerificationTier) -> f64 {
+    match tier {
+        VerificationTier::Standard => 0.2,
+        VerificationTier::EmailVerified => 0.6,
+        VerificationTier::IdentityVerified => 1.0,
+    }
+}
+
+/// Pre-trust for EigenTrust: only accounts above the standard tier seed trust.
+fn seed_weight(tier: VerificationTier) -> f64 {
+    match tier {
+        VerificationTier::Standard => 0.0,
+        VerificationTier::EmailVerified => 0.25,
+        VerificationTier::IdentityVerified => 1.0,
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct UserSignal {
+    id: Uuid,
+    verification_tier: Option<String>,
+    created_at: Option<chrono::DateTime<chrono::Utc>>,
+    vouch_count: i64,
+    propagated_trust: Option<f64>,
+}
+
+/// Cheap fingerprint of everything the score depends on. The job skips a run
+/// when it hasn't changed since the last one.
+async fn graph_fingerprint(pool: &PgPool) -> Result<String, sqlx::Error> {
+    let (fingerprint,): (Option<String>,) = sqlx::query_as(
+        r#"
+        SELECT CONCAT_WS('|',
+            (SELECT COUNT(*) FROM users),
+            (SELECT MAX(updated_at) FROM users),
+            (SELECT COUNT(*) FROM connections WHERE status = 'accepted'),
+            (SELECT MAX(updated_at) FROM connections),
+            (SELECT COUNT(*) FROM vouches),
+            (SELECT MAX(created_at) FROM vouches),
+            CURRENT_DATE
+        )
+        "#
+    )
+        .fetch_one(pool)
+        .await?;
+
+    Ok(fingerprint.unwrap_or_default())
+}
+
+/// Recomputes every user's trust score, warm-starting the propagation from the
+/// previously stored values. Returns the number of scores written.
+pub async fn recompute(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let users: Vec<UserSignal> = sqlx::query_as(
+        r#"
+        SELECT u.id, u.verification_tier, u.created_at,
+               (SELECT COUNT(*) FROM vouches v WHERE v.vouchee_id = u.id) AS vouch_count,
+               ts.propagated_trust
+        FROM users u
+        LEFT JOIN trust_scores ts ON ts.user_id = u.id
+        "#
+    )
+        .fetch_all(pool)
+        .await?;
+
+    let connections: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        "SELECT sender_id, receiver_id FROM connections WHERE status = 'accepted'"
+    )
+        .fetch_all(pool)
+        .await?;
+
+    let vouches: Vec<(Uuid, Uuid)> = sqlx::query_as("SELECT voucher_id, vouchee_id FROM vouches")
+        .fetch_all(pool)
+        .await?;
+
+    let mut edges = Vec::with_capacity(connections.len() * 2 + vouches.len());
+    for (a, b) in connections {
+        edges.push((a, b, CONNECTION_EDGE_WEIGHT));
+        edges.push((b, a, CONNECTION_EDGE_WEIGHT));
+    }
+    for (voucher, vouchee) in vouches {
+        edges.push((voucher, vouchee, VOUCH_EDGE_WEIGHT));
+    }
+
+    let tiers: HashMap<Uuid, VerificationTier> = users
+        .iter()
+        .map(|u| {
+            let tier = u.verification_tier.as_deref()
+                .and_then(VerificationTier::parse)
+                .unwrap_or(VerificationTier::Standard);
+            (u.id, tier)
+        })
+        .collect();
+
+    let nodes: Vec<Uuid> = users.iter().map(|u| u.id).collect();
+    let pre_trust: HashMap<Uuid, f64> = tiers.iter().map(|(id, tier)| (*id, seed_weight(*tier))).collect();
+    let initial: HashMap<Uuid, f64> = users
+        .iter()
+        .filter_map(|u| u.propagated_trust.filter(|t| *t > 0.0).map(|t| (u.id, t)))
+        .collect();
+
+    let propagated = propagation::eigentrust(&nodes, &edges, &pre_trust, &initial);
+    let max_propagated = propagated.values().copied().fold(0.0, f64::max);
+
+    let now = chrono::Utc::now();
+    let mut ids = Vec::with_capacity(users.len());
+    let mut scores = Vec::with_capacity(users.len());
+    let mut tier_parts = Vec::with_capacity(users.len());
+    let mut age_parts = Vec::with_capacity(users.len());
+    let mut vouch_parts = Vec::with_capacity(users.len());
+    let mut propagation_parts = Vec::with_capacity(users.len());
+    let mut raw_trust = Vec::with_capacity(users.len());
+
+    for user in &users {
+        let tier = tier_component(tiers[&user.id]);
+        let age_days = user.created_at.map(|c| (now - c).num_days().max(0) as f64).unwrap_or(0.0);
+        let age = (age_days / MATURE_ACCOUNT_DAYS).min(1.0);
+        let vouch = 1.0 - (-(user.vouch_count as f64) / 3.0).exp();
+        let raw = propagated.get(&user.id).copied().unwrap_or(0.0);
+        let propagation = if max_propagated > 0.0 { raw / max_propagated } else { 0.0 };
+
+        let score = 100.0
+            * (TIER_WEIGHT * tier + AGE_WEIGHT * age + VOUCH_WEIGHT * vouch + PROPAGATION_WEIGHT * propagation);
+
+        ids.push(user.id);
+        scores.push(score);
+        tier_parts.push(tier);
+        age_parts.push(age);
+        vouch_parts.push(vouch);
+        propagation_parts.push(propagation);
+        raw_trust.push(raw);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO trust_scores
+            (user_id, score, tier_component, age_component, vouch_component,
+             propagation_component, propagated_trust, computed_at)
+        SELECT *, NOW() FROM UNNEST($1::uuid[], $2::float8[], $3::float8[], $4::float8[],
+                                    $5::float8[], $6::float8[], $7::float8[])
+        ON CONFLICT (user_id) DO UPDATE SET
+            score = EXCLUDED.score,
+            tier_component = EXCLUDED.tier_component,
+            age_component = EXCLUDED.age_component,
+            vouch_component = EXCLUDED.vouch_component,
+            propagation_component = EXCLUDED.propagation_component,
+            propagated_trust = EXCLUDED.propagated_trust,
+            computed_at = EXCLUDED.computed_at
+        "#
+    )
+        .bind(&ids)
+        .bind(&scores)
+        .bind(&tier_parts)
+        .bind(&age_parts)
+        .bind(&vouch_parts)
+        .bind(&propagation_parts)
+        .bind(&raw_trust)
+        .execute(pool)
+        .await?;
+
+    Ok(ids.len())
+}
+
+/// Background job: recomputes trust scores whenever the graph has changed.
+pub fn spawn_recompute_job(pool: PgPool, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut last_fingerprint = String::new();
+
+        loop {
+            ticker.tick().await;
+
+            let fingerprint = match graph_fingerprint(&pool).await {
+                Ok(fingerprint) => fingerprint,
+                Err(e) => {
+                    eprintln!("Trust score fingerprint error: {}", e);
+                    continue;
+                }
+            };
+
+            if fingerprint == last_fingerprint {
+                continue;
+            }
+
+            match recompute(&pool).await {
+                Ok(count) => {
+                    println!("🔁 Recomputed {} trust scores", count);
+                    last_fingerprint = fingerprint;
+                }
+                Err(e) => eprintln!("Trust score recompute error: {}", e),
+            }
+        }
+    });
+}
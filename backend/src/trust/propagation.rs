@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-9;
+
+/// EigenTrust over a weighted, directed trust graph.
+///
+/// `pre_trust` is the seed distribution (highly verified accounts); every
+/// iteration teleports `1 - DAMPING` of the mass back to it, so trust can only
+/// originate from seeds. `initial` warm-starts the power iteration from the
+/// previous run, which is what keeps incremental recomputation cheap.
+pub fn eigentrust(
+    nodes: &[Uuid],
+    edges: &[(Uuid, Uuid, f64)],
+    pre_trust: &HashMap<Uuid, f64>,
+    initial: &HashMap<Uuid, f64>,
+) -> HashMap<Uuid, f64> {
+    let n = nodes.len();
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    let position: HashMap<Uuid, usize> = nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+    let mut seeds: Vec<f64> = nodes.iter().map(|id| pre_trust.get(id).copied().unwrap_or(0.0)).collect();
+    normalize_or_uniform(&mut seeds);
+
+    let mut outgoing: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+    for (from, to, weight) in edges {
+        if let (Some(&i), Some(&j)) = (position.get(from), position.get(to))
+            && i != j
+            && *weight > 0.0
+        {
+            outgoing[i].push((j, *weight));
+        }
+    }
+    let out_weight: Vec<f64> = outgoing.iter().map(|edges| edges.iter().map(|(_, w)| w).sum()).collect();
+
+    let mut trust: Vec<f64> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, id)| initial.get(id).copied().unwrap_or(seeds[i]))
+        .collect();
+    normalize_or_uniform(&mut trust);
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut next = vec![0.0; n];
+        let mut dangling = 0.0;
+
+        for i in 0..n {
+            if out_weight[i] == 0.0 {
+                dangling += trust[i];
+                continue;
+            }
+            for &(j, weight) in &outgoing[i] {
+                next[j] += trust[i] * weight / out_weight[i];
+            }
+        }
+
+        // Mass from nodes without outgoing trust returns to the seeds
+        for i in 0..n {
+            next[i] = DAMPING * (next[i] + dangling * seeds[i]) + (1.0 - DAMPING) * seeds[i];
+        }
+
+        let delta: f64 = next.iter().zip(&trust).map(|(a, b)| (a - b).abs()).sum();
+        trust = next;
+
+        if delta < TOLERANCE {
+            break;
+        }
+    }
+
+    nodes.iter().copied().zip(trust).collect()
+}
+
+fn normalize_or_uniform(values: &mut [f64]) {
+    let total: f64 = values.iter().sum();
+
+    if total > 0.0 {
+        values.iter_mut().for_each(|v| *v /= total);
+    } else {
+        let uniform = 1.0 / values.len() as f64;
+        values.iter_mut().for_each(|v| *v = uniform);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    #[test]
+    fn empty_graph_has_no_scores() {
+        assert!(eigentrust(&[], &[], &HashMap::new(), &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn scores_form_a_distribution() {
+        let nodes = ids(4);
+        let edges = vec![
+            (nodes[0], nodes[1], 1.0),
+            (nodes[1], nodes[2], 3.0),
+            (nodes[2], nodes[0], 1.0),
+            (nodes[2], nodes[3], 1.0),
+        ];
+        let pre_trust = HashMap::from([(nodes[0], 1.0)]);
+
+        let trust = eigentrust(&nodes, &edges, &pre_trust, &HashMap::new());
+
+        assert_eq!(trust.len(), 4);
+        assert!((trust.values().sum::<f64>() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn trust_only_flows_from_seeds() {
+        let nodes = ids(4);
+        // 2 and 3 vouch for each other but nobody trusted points at them
+        let edges = vec![
+            (nodes[0], nodes[1], 1.0),
+            (nodes[1], nodes[0], 1.0),
+            (nodes[2], nodes[3], 1.0),
+            (nodes[3], nodes[2], 1.0),
+        ];
+        let pre_trust = HashMap::from([(nodes[0], 1.0)]);
+
+        let trust = eigentrust(&nodes, &edges, &pre_trust, &HashMap::new());
+
+        assert!(trust[&nodes[1]] > 0.1);
+        assert!(trust[&nodes[2]] < 1e-6);
+        assert!(trust[&nodes[3]] < 1e-6);
+    }
+
+    #[test]
+    fn heavier_edges_carry_more_trust() {
+        let nodes = ids(3);
+        let edges = vec![(nodes[0], nodes[1], 3.0), (nodes[0], nodes[2], 1.0)];
+        let pre_trust = HashMap::from([(nodes[0], 1.0)]);
+
+        let trust = eigentrust(&nodes, &edges, &pre_trust, &HashMap::new());
+
+        assert!(trust[&nodes[1]] > trust[&nodes[2]]);
+    }
+
+    #[test]
+    fn ignores_self_loops_and_unknown_nodes() {
+        let nodes = ids(2);
+        let stranger = Uuid::new_v4();
+        let pre_trust = HashMap::from([(nodes[0], 1.0)]);
+
+        let plain = eigentrust(&nodes, &[(nodes[0], nodes[1], 1.0)], &pre_trust, &HashMap::new());
+        let noisy = eigentrust(
+            &nodes,
+            &[(nodes[0], nodes[1], 1.0), (nodes[1], nodes[1], 5.0), (nodes[0], stranger, 5.0)],
+            &pre_trust,
+            &HashMap::new(),
+        );
+
+        assert!(!noisy.contains_key(&stranger));
+        for id in &nodes {
+            assert!((plain[id] - noisy[id]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn warm_start_converges_to_the_same_scores() {
+        let nodes = ids(3);
+        let edges = vec![(nodes[0], nodes[1], 1.0), (nodes[1], nodes[2], 1.0), (nodes[2], nodes[0], 1.0)];
+        let pre_trust = HashMap::from([(nodes[0], 1.0)]);
+
+        let cold = eigentrust(&nodes, &edges, &pre_trust, &HashMap::new());
+        let skewed = HashMap::from([(nodes[2], 1.0), (nodes[0], 0.0), (nodes[1], 0.0)]);
+        let warm = eigentrust(&nodes, &edges, &pre_trust, &skewed);
+
+        for id in &nodes {
+            assert!((cold[id] - warm[id]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn no_seeds_falls_back_to_uniform() {
+        let nodes = ids(3);
+
+        let trust = eigentrust(&nodes, &[], &HashMap::new(), &HashMap::new());
+
+        for id in &nodes {
+            assert!((trust[id] - 1.0 / 3.0).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! vCard 4.0 (RFC 6350) serialization.
+
+/// Properties are written in the order they're added.
+pub struct VCard {
+    lines: Vec<String>,
+}
+
+/// Escapes a text value: backslash, comma, semicolon and newlines.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ',' => escaped.push_str("\\,"),
+            ';' => escaped.push_str("\\;"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Folds a content line at 75 octets, never splitting a UTF-8 character.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut width = 0;
+
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+
+    folded
+}
+
+impl VCard {
+    /// A card for `full_name`. FN is the only property vCard 4.0 requires.
+    pub fn new(full_name: &str) -> Self {
+        let mut card = Self { lines: vec!["BEGIN:VCARD".to_string(), "VERSION:4.0".to_string()] };
+        card.text("FN", full_name);
+        card
+    }
+
+    /// Adds a text property, escaping the value.
+    pub fn text(&mut self, name: &str, value: &str) -> &mut Self {
+        self.lines.push(format!("{}:{}", name, escape(value)));
+        self
+    }
+
+    /// Adds a structured property such as N or ADR, whose components are
+    /// separated by semicolons and escaped individually.
+    pub fn structured(&mut self, name: &str, components: &[&str]) -> &mut Self {
+        let value: Vec<String> = components.iter().map(|component| escape(component)).collect();
+        self.lines.push(format!("{}:{}", name, value.join(";")));
+        self
+    }
+
+    /// Adds a URI property (URL, PHOTO, SOURCE), which is not escaped.
+    pub fn uri(&mut self, name: &str, uri: &str) -> &mut Self {
+        self.lines.push(format!("{}:{}", name, uri));
+        self
+    }
+
+    pub fn build(&self) -> String {
+        let mut out = String::new();
+        for line in self.lines.iter().map(String::as_str).chain(["END:VCARD"]) {
+            out.push_str(&fold(line));
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(escape(r"a\b,c;d"), r"a\\b\,c\;d");
+        assert_eq!(escape("line one\r\nline two\nthree"), r"line one\nline two\nthree");
+    }
+
+    #[test]
+    fn escapes_structured_components_separately() {
+        let card = VCard::new("Ada Lovelace").structured("N", &["Lovelace", "Ada;Augusta", ""]).build();
+
+        assert!(card.contains("\r\nN:Lovelace;Ada\\;Augusta;\r\n"));
+    }
+
+    #[test]
+    fn leaves_uris_alone() {
+        let card = VCard::new("Ada").uri("URL", "https://example.com/a,b;c").build();
+
+        assert!(card.contains("\r\nURL:https://example.com/a,b;c\r\n"));
+    }
+
+    #[test]
+    fn short_lines_are_not_folded() {
+        let line = "x".repeat(75);
+        assert_eq!(fold(&line), line);
+    }
+
+    #[test]
+    fn folds_long_lines_at_75_octets() {
+        let folded = fold(&"x".repeat(160));
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].len(), 75);
+        assert!(lines[1..].iter().all(|line| line.starts_with(' ') && line.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), "x".repeat(160));
+    }
+
+    #[test]
+    fn never_splits_a_multibyte_character() {
+        let folded = fold(&"é".repeat(60));
+
+        for line in folded.split("\r\n") {
+            assert!(line.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), "é".repeat(60));
+    }
+
+    #[test]
+    fn builds_a_complete_card() {
+        let card = VCard::new("Ada").text("TITLE", "Engineer").build();
+
+        assert_eq!(card, "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Ada\r\nTITLE:Engineer\r\nEND:VCARD\r\n");
+    }
+}
@@ -1,11 +1,18 @@
 import React, { useState, useEffect } from 'react';
 
+interface UserCard {
+  id: string;
+  handle: string;
+  full_name: string;
+  profile_picture_url: string | null;
+  email?: string;
+  headline?: string;
+}
+
 interface ConnectionRequest {
   id: string;
-  sender_id: string;
-  sender_name: string;
-  sender_email: string;
-  created_at: string;
+  sender: UserCard;
+  requested_at: string;
 }
 
 const ConnectionRequests: React.FC = () => {
@@ -167,19 +174,19 @@ const ConnectionRequests: React.FC = () => {
             >
               <div style={{ flex: 1 }}>
                 <h4 style={{ margin: '0 0 0.25rem 0', color: '#1f2937' }}>
-                  {request.sender_name}
+                  {request.sender.full_name}
                 </h4>
                 <p style={{ margin: '0 0 0.25rem 0', color: '#6b7280', fontSize: '0.875rem' }}>
-                  {request.sender_email}
+                  {request.sender.email}
                 </p>
                 <p style={{ margin: 0, color: '#9ca3af', fontSize: '0.75rem' }}>
-                  Request sent {new Date(request.created_at).toLocaleDateString()}
+                  Request sent {new Date(request.requested_at).toLocaleDateString()}
                 </p>
               </div>
               
               <div style={{ display: 'flex', gap: '0.5rem' }}>
                 <button
-                  onClick={() => handleAccept(request.id, request.sender_name)}
+                  onClick={() => handleAccept(request.id, request.sender.full_name)}
                   style={{
                     background: '#10b981',
                     color: 'white',
@@ -193,7 +200,7 @@ const ConnectionRequests: React.FC = () => {
                   Accept
                 </button>
                 <button
-                  onClick={() => handleReject(request.id, request.sender_name)}
+                  onClick={() => handleReject(request.id, request.sender.full_name)}
                   style={{
                     background: '#ef4444',
                     color: 'white',
@@ -1,11 +1,18 @@
 import React, { useState, useEffect } from 'react';
 
-interface Connection {
+interface UserCard {
   id: string;
-  user_id: string;
+  handle: string;
   full_name: string;
-  email: string;
   profile_picture_url: string | null;
+  email?: string;
+  headline?: string;
+}
+
+interface Connection {
+  id: string;
+  user: UserCard;
+  trust_score: number | null;
   connected_at: string;
 }
 
@@ -133,14 +140,14 @@ const ConnectionsList: React.FC = () => {
                   fontSize: '1.125rem',
                   marginRight: '1rem'
                 }}>
-                  {connection.full_name.split(' ').map(n => n[0]).join('').toUpperCase()}
+                  {connection.user.full_name.split(' ').map(n => n[0]).join('').toUpperCase()}
                 </div>
                 <div>
                   <h4 style={{ margin: '0 0 0.25rem 0', color: '#1f2937' }}>
-                    {connection.full_name}
+                    {connection.user.full_name}
                   </h4>
                   <p style={{ margin: 0, color: '#6b7280', fontSize: '0.875rem' }}>
-                    {connection.email}
+                    {connection.user.email}
                   </p>
                 </div>
               </div>
@@ -2,7 +2,8 @@ import React, { useState } from 'react';
 
 interface User {
   id: string;
-  email: string;
+  handle: string;
+  email?: string;
   full_name: string;
   headline?: string;
 }